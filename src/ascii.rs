@@ -1,49 +1,245 @@
 use include_dir::{include_dir, Dir};
 use crossterm::style::{Color, SetForegroundColor, ResetColor};
-use crate::config::Config;
+use crate::config::{user_config_dir, Config, LogoVariant};
+use crate::ui::{parse_hex_color, split_colors};
+use std::path::PathBuf;
 
 static LOGOS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/logos");
 
-fn parse_logo_color(idx: usize, config: &Config) -> Color {
-    config.logo_color
-        .as_ref()
-        .and_then(|arr| arr.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()).collect::<Vec<_>>().get(idx - 1).copied())
-        .and_then(|c| super::ui::parse_hex_color(c))
-        .unwrap_or(Color::White)
+// colors for $1..$9 when neither the config nor the logo has any
+const DEFAULT_LOGO_COLORS: &str = "#00FFFF #FF00FF #FFFF00 #FFFFFF";
+
+// each built-in logo's own colors, roughly the ones fastfetch gives them. Variants
+// (arch_small, arch2, arch_old) share the base logo's entry
+const LOGO_PALETTES: &[(&str, &[&str])] = &[
+    ("almalinux", &["#FF4649", "#FFC33F", "#0069DA", "#86DA2F", "#24C2FF"]),
+    ("alpine", &["#0D597F", "#FFFFFF"]),
+    ("android", &["#3DDC84", "#FFFFFF"]),
+    ("arch", &["#1793D1", "#1793D1"]),
+    ("artix", &["#10A0CC", "#FFFFFF"]),
+    ("cachyos", &["#00CCCC", "#3BA55D", "#FFFFFF"]),
+    ("centos", &["#EFA724", "#9CCD2A", "#262577", "#932279"]),
+    ("debian", &["#D70A53", "#FFFFFF"]),
+    ("elementary", &["#64BAFF", "#FFFFFF"]),
+    ("endeavouros", &["#C061CB", "#E4544F", "#7F7FFF"]),
+    ("fedora", &["#51A2DA", "#FFFFFF"]),
+    ("freebsd", &["#AB2B28", "#FFFFFF"]),
+    ("garuda", &["#FF7A00", "#FFFFFF"]),
+    ("gentoo", &["#9F8FD8", "#FFFFFF"]),
+    ("kali", &["#367BF0", "#FFFFFF"]),
+    ("linux", &["#FFFFFF", "#555555", "#FFCC00"]),
+    ("linuxmint", &["#87CF3E", "#FFFFFF"]),
+    ("macos", &["#61BB46", "#FDB827", "#F5821F", "#E03A3E", "#963D97", "#009DDC"]),
+    ("manjaro", &["#35BF5C", "#FFFFFF"]),
+    ("nixos", &["#5277C3", "#7EBAE4"]),
+    ("opensuse", &["#73BA25", "#FFFFFF"]),
+    ("opensuse_leap", &["#73BA25", "#FFFFFF"]),
+    ("opensuse_tumbleweed", &["#73BA25", "#FFFFFF"]),
+    ("pop", &["#48B9C7", "#FFFFFF"]),
+    ("raspbian", &["#75A928", "#BC1142"]),
+    ("rhel", &["#EE0000", "#FFFFFF"]),
+    ("rocky", &["#10B981", "#FFFFFF"]),
+    ("slackware", &["#4A6EB7", "#FFFFFF"]),
+    ("ubuntu", &["#E95420", "#FFFFFF"]),
+    ("void", &["#62B86C", "#FFFFFF"]),
+    ("windows", &["#F35325", "#81BC06", "#05A6F0", "#FFBA08"]),
+    ("windows_11", &["#00A4EF", "#00A4EF", "#00A4EF", "#00A4EF"]),
+    ("zorin", &["#15A6F0", "#FFFFFF"]),
+];
+
+// logo_color from the config wins, then the logo's own palette, then the defaults
+fn logo_palette(logo_name: Option<&str>, config: &Config) -> Vec<Color> {
+    let colors = match (&config.logo_color, logo_name.and_then(builtin_palette)) {
+        (Some(colors), _) => split_colors(colors),
+        (None, Some(palette)) => palette.to_vec(),
+        (None, None) => split_colors(DEFAULT_LOGO_COLORS),
+    };
+    colors
+        .iter()
+        .map(|c| parse_hex_color(c).unwrap_or(Color::White))
+        .collect()
 }
 
+fn builtin_palette(name: &str) -> Option<&'static [&'static str]> {
+    let find = |name: &str| {
+        LOGO_PALETTES
+            .iter()
+            .find(|(logo, _)| *logo == name)
+            .map(|(_, palette)| *palette)
+    };
+    find(name).or_else(|| {
+        let base = name
+            .trim_end_matches("_small")
+            .trim_end_matches("_old")
+            .trim_end_matches(|c: char| c.is_ascii_digit());
+        find(base)
+    })
+}
 
-pub fn process_logo_colors(logo: &str, config: &Config) -> String {
-    let mut result = String::new();
-    let mut chars = logo.chars().peekable();
-    let mut current_color = SetForegroundColor(Color::White).to_string();
+/// Turns fastfetch's logo markup into colors: `$1`..`$9` (or neofetch's `${c1}`) switch
+/// color and `$$` is a literal dollar. Every line ends in a reset and starts by picking its
+/// color back up, so nothing bleeds into the box next to it.
+pub fn process_logo_colors(logo: &str, logo_name: Option<&str>, config: &Config) -> String {
+    let palette = logo_palette(logo_name, config);
+    let color = |n: u32| SetForegroundColor(palette.get(n as usize - 1).copied().unwrap_or(Color::White)).to_string();
+    // like fastfetch, a logo starts out in its first color
+    let mut current_color = color(1);
 
-    while let Some(c) = chars.next() {
-        if c == '$' {
-            // Check for a digit after $
-            if let Some(digit) = chars.peek().and_then(|d| d.to_digit(10)) {
-                chars.next(); // consume digit
-                let color = parse_logo_color(digit as usize, config);
-                current_color = format!("{}", SetForegroundColor(color));
-                result.push_str(&current_color);
+    let mut lines = Vec::new();
+    for line in logo.lines() {
+        let mut result = current_color.clone();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                result.push(c);
                 continue;
             }
+            match chars.peek() {
+                Some('$') => {
+                    chars.next();
+                    result.push('$');
+                }
+                Some(&d) if ('1'..='9').contains(&d) => {
+                    chars.next();
+                    current_color = color(d.to_digit(10).unwrap_or(1));
+                    result.push_str(&current_color);
+                }
+                Some('{') => {
+                    // "${c3}"
+                    let token: String = chars.clone().take(4).collect();
+                    match token.strip_prefix("{c").and_then(|t| t.strip_suffix('}')).and_then(|d| d.parse::<u32>().ok()) {
+                        Some(n @ 1..=9) => {
+                            chars.nth(3);
+                            current_color = color(n);
+                            result.push_str(&current_color);
+                        }
+                        _ => result.push('$'),
+                    }
+                }
+                // anything else after a '$' is just part of the art
+                _ => result.push('$'),
+            }
         }
-        result.push(c);
-        // If we hit a newline, also push the current color at the start of the next line
-        if c == '\n' {
-            result.push_str(&current_color);
+        result.push_str(&ResetColor.to_string());
+        lines.push(result);
+    }
+    lines.join("\n")
+}
+
+// ~/.config/zfetch/logos/<name>.txt, looked at before the built-in set so a company logo
+// can be dropped in (or a built-in one replaced) without recompiling
+fn user_logo(name: &str) -> Option<PathBuf> {
+    let path = user_logo_dir()?.join(format!("{}.txt", name));
+    path.is_file().then_some(path)
+}
+
+fn user_logo_dir() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join("logos"))
+}
+
+pub fn logo_exists(distro_id: &str) -> bool {
+    user_logo(distro_id).is_some() || LOGOS_DIR.get_file(format!("{}.txt", distro_id)).is_some()
+}
+
+/// Picks the logo for a distro: its own id first, then whatever it's based on (ID_LIKE,
+/// e.g. "ubuntu debian" for Mint) and finally plain Tux on any other Linux.
+pub fn resolve_logo(distro_id: &str, distro_like: &[String]) -> String {
+    let fallback = cfg!(target_os = "linux").then_some("linux");
+    std::iter::once(distro_id)
+        .chain(distro_like.iter().map(String::as_str))
+        .map(logo_name)
+        .chain(fallback.map(str::to_string))
+        .find(|name| logo_exists(name))
+        .unwrap_or_else(|| distro_id.to_string())
+}
+
+// os-release ids use dashes ("opensuse-tumbleweed"), the logo files use underscores
+fn logo_name(id: &str) -> String {
+    id.to_lowercase().replace(['-', ' '], "_")
+}
+
+/// "arch" + small -> "arch_small" when that exists, otherwise plain "arch".
+pub fn logo_variant(name: &str, variant: LogoVariant) -> String {
+    let candidates = match variant {
+        LogoVariant::Default => Vec::new(),
+        LogoVariant::Small => vec![format!("{}_small", name)],
+        LogoVariant::Alt => vec![format!("{}2", name), format!("{}_old", name)],
+    };
+    candidates
+        .into_iter()
+        .find(|candidate| logo_exists(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Every logo name (the user's own and the embedded ones), sorted, for --list-logos.
+pub fn logo_names() -> Vec<String> {
+    let mut names: Vec<String> = LOGOS_DIR
+        .files()
+        .filter_map(|file| file.path().file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .collect();
+    if let Some(Ok(entries)) = user_logo_dir().map(std::fs::read_dir) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_some_and(|ext| ext == "txt")
+                && let Some(stem) = path.file_stem()
+            {
+                names.push(stem.to_string_lossy().into_owned());
+            }
         }
     }
-    result.push_str(&format!("{}", ResetColor));
-    result
+    names.sort();
+    names.dedup();
+    names
 }
 
 pub async fn get_ascii_logo(distro_id: &str) -> String {
+    if let Some(logo) = user_logo(distro_id).and_then(|path| std::fs::read_to_string(path).ok()) {
+        return logo;
+    }
     let filename = format!("{}.txt", distro_id);
     if let Some(file) = LOGOS_DIR.get_file(&filename) {
         file.contents_utf8().unwrap_or("Logo not found").to_string()
     } else {
         "Logo not found".to_string()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fg(r: u8, g: u8, b: u8) -> String {
+        SetForegroundColor(Color::Rgb { r, g, b }).to_string()
+    }
+
+    fn render(logo: &str) -> String {
+        let config = Config {
+            logo_color: Some("#FF0000 #00FF00 #0000FF".to_string()),
+            ..Config::default()
+        };
+        process_logo_colors(logo, None, &config)
+    }
+
+    #[test]
+    fn dollar_dollar_is_a_dollar() {
+        assert_eq!(render("a$$b"), format!("{}a$b{}", fg(255, 0, 0), ResetColor));
+    }
+
+    #[test]
+    fn neofetch_markers() {
+        assert_eq!(render("${c3}x"), format!("{}{}x{}", fg(255, 0, 0), fg(0, 0, 255), ResetColor));
+    }
+
+    #[test]
+    fn unknown_markers_stay_verbatim() {
+        // $0 used to underflow the palette index
+        assert_eq!(render("$0 $x ${c0} $"), format!("{}$0 $x ${{c0}} ${}", fg(255, 0, 0), ResetColor));
+    }
+
+    #[test]
+    fn every_line_resets_and_carries_its_color_over() {
+        let lines: Vec<String> = render("$2ab\ncd").lines().map(str::to_string).collect();
+        assert_eq!(lines[0], format!("{}{}ab{}", fg(255, 0, 0), fg(0, 255, 0), ResetColor));
+        assert_eq!(lines[1], format!("{}cd{}", fg(0, 255, 0), ResetColor));
+    }
+}
@@ -0,0 +1,281 @@
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+use crate::config::{BoxPreset, ByteUnits, Config, ImageProtocol, LogoPosition, LogoType, LogoVariant, MAX_PRECISION};
+use crate::ascii::logo_exists;
+use crate::ui::{parse_hex_color, split_colors};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Logo plus the info box (the default)
+    Text,
+    /// Everything as JSON with raw numbers (bytes, seconds, percent), for scripts
+    Json,
+}
+
+// every show_* field in Config gets a --thing / --no-thing pair, so a single run can
+// flip whatever the config file says without editing it
+#[derive(Parser, Debug)]
+#[command(name = "zfetch", version, about = "A blazing-fast system information fetcher")]
+pub struct Cli {
+    /// Extra config file, layered over the ones zfetch finds on its own
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Show the logo for this id instead of the detected distro (e.g. arch, macos)
+    #[arg(long, value_name = "ID", value_parser = logo_name)]
+    pub logo: Option<String>,
+
+    /// Color of the info box, as hex (e.g. "#FF5733")
+    #[arg(long, value_name = "HEX", value_parser = hex_color)]
+    pub color: Option<String>,
+
+    /// Border of the info box
+    #[arg(long, value_enum, value_name = "STYLE")]
+    pub box_style: Option<BoxPreset>,
+
+    /// Use the small or alternative version of the logo when there is one
+    #[arg(long, value_enum, value_name = "VARIANT")]
+    pub logo_variant: Option<LogoVariant>,
+
+    /// Where to put the logo ("none" shows only the info box)
+    #[arg(long, value_enum)]
+    pub logo_position: Option<LogoPosition>,
+
+    /// Draw this text file as the logo (same $1..$9 color markers as the built-in ones)
+    #[arg(long, value_name = "PATH")]
+    pub logo_path: Option<PathBuf>,
+
+    /// Treat --logo-path / logo_path as text or as a picture
+    #[arg(long, value_enum)]
+    pub logo_type: Option<LogoType>,
+
+    /// Graphics protocol for image logos
+    #[arg(long, value_enum)]
+    pub image_protocol: Option<ImageProtocol>,
+
+    /// Width of an image logo in columns
+    #[arg(long, value_name = "COLUMNS", value_parser = clap::value_parser!(u16).range(1..))]
+    pub image_width: Option<u16>,
+
+    /// Print the name of every logo (built-in and ~/.config/zfetch/logos/) and exit
+    #[arg(long)]
+    pub list_logos: bool,
+
+    /// Colors for the logo's $1..$9 markers, comma or space separated
+    #[arg(long, value_name = "HEX...", value_parser = hex_color_list)]
+    pub logo_color: Option<String>,
+
+    /// Units for memory, swap and disk sizes
+    #[arg(long, value_enum)]
+    pub units: Option<ByteUnits>,
+
+    /// Decimal places for memory, swap and disk sizes
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=MAX_PRECISION as i64))]
+    pub precision: Option<u8>,
+
+    /// Load and validate every config file, report problems and exit (non-zero if any)
+    #[arg(long)]
+    pub check_config: bool,
+
+    /// Write a documented default config (to ~/.config/zfetch/config.toml unless PATH is given) and exit
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub gen_config: Option<Option<PathBuf>>,
+
+    /// Let --gen-config overwrite an existing file
+    #[arg(long, requires = "gen_config")]
+    pub force: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Show the distro name
+    #[arg(long, overrides_with = "no_distro")]
+    distro: bool,
+    /// Hide the distro name
+    #[arg(long, overrides_with = "distro")]
+    no_distro: bool,
+
+    /// Show the distro id
+    #[arg(long, overrides_with = "no_distro_id")]
+    distro_id: bool,
+    /// Hide the distro id
+    #[arg(long, overrides_with = "distro_id")]
+    no_distro_id: bool,
+
+    /// Show the kernel version
+    #[arg(long, overrides_with = "no_kernel")]
+    kernel: bool,
+    /// Hide the kernel version
+    #[arg(long, overrides_with = "kernel")]
+    no_kernel: bool,
+
+    /// Show the CPU
+    #[arg(long, overrides_with = "no_cpu")]
+    cpu: bool,
+    /// Hide the CPU
+    #[arg(long, overrides_with = "cpu")]
+    no_cpu: bool,
+
+    /// Show the GPU
+    #[arg(long, overrides_with = "no_gpu")]
+    gpu: bool,
+    /// Hide the GPU (also skips GPU detection)
+    #[arg(long, overrides_with = "gpu")]
+    no_gpu: bool,
+
+    /// Show live GPU temperature, load and clock
+    #[arg(long, overrides_with = "no_gpu_stats")]
+    gpu_stats: bool,
+    /// Hide live GPU stats
+    #[arg(long, overrides_with = "gpu_stats")]
+    no_gpu_stats: bool,
+
+    /// Show memory usage
+    #[arg(long, overrides_with = "no_memory")]
+    memory: bool,
+    /// Hide memory usage
+    #[arg(long, overrides_with = "memory")]
+    no_memory: bool,
+
+    /// Show swap usage
+    #[arg(long, overrides_with = "no_swap")]
+    swap: bool,
+    /// Hide swap usage
+    #[arg(long, overrides_with = "swap")]
+    no_swap: bool,
+
+    /// Show the local IP address
+    #[arg(long, overrides_with = "no_local_ip")]
+    local_ip: bool,
+    /// Hide the local IP address
+    #[arg(long, overrides_with = "local_ip")]
+    no_local_ip: bool,
+
+    /// Show the battery status
+    #[arg(long, overrides_with = "no_battery")]
+    battery: bool,
+    /// Hide the battery status
+    #[arg(long, overrides_with = "battery")]
+    no_battery: bool,
+
+    /// Show disk usage
+    #[arg(long, overrides_with = "no_storage")]
+    storage: bool,
+    /// Hide disk usage
+    #[arg(long, overrides_with = "storage")]
+    no_storage: bool,
+
+    /// Show the uptime
+    #[arg(long, overrides_with = "no_uptime")]
+    uptime: bool,
+    /// Hide the uptime
+    #[arg(long, overrides_with = "uptime")]
+    no_uptime: bool,
+
+    /// Show user@host in the box title
+    #[arg(long, overrides_with = "no_user_host")]
+    user_host: bool,
+    /// Hide user@host in the box title
+    #[arg(long, overrides_with = "user_host")]
+    no_user_host: bool,
+
+    /// Show removable drives in the disk list
+    #[arg(long, overrides_with = "no_removable")]
+    removable: bool,
+    /// Hide removable drives from the disk list
+    #[arg(long, overrides_with = "removable")]
+    no_removable: bool,
+}
+
+impl Cli {
+    /// Layers whatever was passed on the command line over the loaded config.
+    pub fn apply(&self, config: &mut Config) {
+        toggle(&mut config.show_distro, self.distro, self.no_distro);
+        toggle(&mut config.show_distro_id, self.distro_id, self.no_distro_id);
+        toggle(&mut config.show_kernel, self.kernel, self.no_kernel);
+        toggle(&mut config.show_cpu, self.cpu, self.no_cpu);
+        toggle(&mut config.show_gpu, self.gpu, self.no_gpu);
+        toggle(&mut config.show_gpu_stats, self.gpu_stats, self.no_gpu_stats);
+        toggle(&mut config.show_memory, self.memory, self.no_memory);
+        toggle(&mut config.show_swap, self.swap, self.no_swap);
+        toggle(&mut config.show_local_ip, self.local_ip, self.no_local_ip);
+        toggle(&mut config.show_battery, self.battery, self.no_battery);
+        toggle(&mut config.show_storage, self.storage, self.no_storage);
+        toggle(&mut config.show_uptime, self.uptime, self.no_uptime);
+        toggle(&mut config.show_user_host, self.user_host, self.no_user_host);
+        if self.removable || self.no_removable {
+            let storage = config.storage.get_or_insert_with(Default::default);
+            toggle(&mut storage.show_removable, self.removable, self.no_removable);
+        }
+
+        if let Some(logo) = &self.logo {
+            config.logo = Some(logo.clone());
+        }
+        if self.logo_position.is_some() {
+            let layout = config.layout.get_or_insert_with(Default::default);
+            layout.logo_position = self.logo_position;
+        }
+        if let Some(logo_path) = &self.logo_path {
+            config.logo_path = Some(logo_path.clone());
+        }
+        if self.logo_type.is_some() {
+            config.logo_type = self.logo_type;
+        }
+        if self.image_protocol.is_some() {
+            config.image_protocol = self.image_protocol;
+        }
+        if self.image_width.is_some() {
+            config.image_width = self.image_width;
+        }
+        if self.logo_variant.is_some() {
+            config.logo_variant = self.logo_variant;
+        }
+        if let Some(color) = &self.color {
+            config.color = Some(color.clone());
+        }
+        if let Some(preset) = self.box_style {
+            config.box_style = Some(preset.into());
+        }
+        if let Some(logo_color) = &self.logo_color {
+            config.logo_color = Some(logo_color.clone());
+        }
+        if self.units.is_some() {
+            config.units = self.units;
+        }
+        if self.precision.is_some() {
+            config.precision = self.precision;
+        }
+    }
+}
+
+// clap's overrides_with makes sure at most one of the pair is set (last one wins)
+fn toggle(field: &mut Option<bool>, on: bool, off: bool) {
+    if on {
+        *field = Some(true);
+    } else if off {
+        *field = Some(false);
+    }
+}
+
+fn hex_color(value: &str) -> Result<String, String> {
+    match parse_hex_color(value) {
+        Some(_) => Ok(value.to_string()),
+        None => Err(format!("\"{}\" is not a hex color like \"#FF5733\"", value)),
+    }
+}
+
+fn logo_name(value: &str) -> Result<String, String> {
+    if logo_exists(value) {
+        Ok(value.to_string())
+    } else {
+        Err(format!("there is no logo called \"{}\" (`zfetch --list-logos` shows them all)", value))
+    }
+}
+
+fn hex_color_list(value: &str) -> Result<String, String> {
+    for color in split_colors(value) {
+        hex_color(color)?;
+    }
+    Ok(value.to_string())
+}
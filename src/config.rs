@@ -1,12 +1,37 @@
-use serde::Deserialize;
+use clap::ValueEnum;
+use serde::de::{self, IgnoredAny, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use crate::ascii::logo_exists;
+use crate::ui::{parse_hex_color, split_colors};
+use std::env;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthChar;
 
-#[derive(Deserialize, Debug)]
+// fills in only the fields `other` actually sets, so a file that mentions one key
+// doesn't wipe out everything layered underneath it
+macro_rules! merge_fields {
+    ($dst:expr, $src:expr, $($field:ident),* $(,)?) => {
+        $(
+            if $src.$field.is_some() {
+                $dst.$field = $src.$field;
+            }
+        )*
+    };
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Config {
+    // the per-module show_* switches are deprecated in favour of `modules`. they still hide
+    // a row (older configs, and what --no-<name> sets) but --gen-config no longer writes them
     pub show_distro: Option<bool>,
     pub show_distro_id: Option<bool>,
     pub show_kernel: Option<bool>,
     pub show_cpu: Option<bool>,
     pub show_gpu: Option<bool>,
+    pub show_gpu_stats: Option<bool>,
     pub show_memory: Option<bool>,
     pub show_swap: Option<bool>,
     pub show_local_ip: Option<bool>,
@@ -16,43 +41,1166 @@ pub struct Config {
     pub logo_color: Option<String>,
     pub color: Option<String>,
     pub show_user_host: Option<bool>,
+    pub logo: Option<String>,
+    pub logo_variant: Option<LogoVariant>,
+    /// A logo file of your own, same `$1`..`$9` markup as the built-in ones
+    pub logo_path: Option<PathBuf>,
+    pub logo_type: Option<LogoType>,
+    pub image_protocol: Option<ImageProtocol>,
+    /// How many columns an image logo takes up, the rows follow from its aspect ratio
+    #[serde(default, deserialize_with = "checked_int")]
+    pub image_width: Option<u16>,
+    pub units: Option<ByteUnits>,
+    #[serde(default, deserialize_with = "checked_int")]
+    pub precision: Option<u8>,
+    pub storage: Option<StorageConfig>,
+    pub layout: Option<LayoutConfig>,
+    pub box_style: Option<BoxStyle>,
+    /// Rows of the info box, in order
+    pub modules: Option<Vec<Module>>,
+}
+
+/// Which filesystems get a "Disk (...)" row. Mount patterns are globs (`/mnt/*`).
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct StorageConfig {
+    /// When set, only mounts matching one of these are shown
+    pub include_mounts: Option<Vec<String>>,
+    pub exclude_mounts: Option<Vec<String>>,
+    pub exclude_fs_types: Option<Vec<String>>,
+    /// Hide anything smaller than this, either bytes or a string like "1 GiB"
+    pub min_size: Option<Size>,
+    pub show_removable: Option<bool>,
+}
+
+impl StorageConfig {
+    pub fn merge(&mut self, other: StorageConfig) {
+        merge_fields!(
+            self, other,
+            include_mounts,
+            exclude_mounts,
+            exclude_fs_types,
+            min_size,
+            show_removable,
+        );
+    }
+
+    fn validate(&self, problems: &mut Vec<String>) {
+        let patterns = [
+            ("storage.include_mounts", &self.include_mounts),
+            ("storage.exclude_mounts", &self.exclude_mounts),
+        ];
+        for (key, list) in patterns {
+            for pattern in list.iter().flatten() {
+                if let Err(err) = glob::Pattern::new(pattern) {
+                    problems.push(format!("`{}`: \"{}\" is not a valid glob ({})", key, pattern, err.msg));
+                }
+            }
+        }
+        if let Some(Size::Text(text)) = &self.min_size
+            && parse_size(text).is_none()
+        {
+            problems.push(format!("`storage.min_size`: \"{}\" is not a size like \"512 MiB\" or \"1 GB\"", text));
+        }
+    }
+}
+
+/// Where the logo goes relative to the info box.
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct LayoutConfig {
+    pub logo_position: Option<LogoPosition>,
+    /// Columns between the logo and the box when they're side by side
+    #[serde(default, deserialize_with = "checked_int")]
+    pub gap: Option<u16>,
+    /// Where the shorter of the two columns sits when they're side by side
+    pub align: Option<VerticalAlign>,
+}
+
+impl LayoutConfig {
+    pub fn merge(&mut self, other: LayoutConfig) {
+        merge_fields!(self, other, logo_position, gap, align);
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogoPosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Just the info box
+    None,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum VerticalAlign {
+    Top,
+    Center,
+}
+
+pub const DEFAULT_GAP: u16 = 4;
+
+/// One row (or a group of rows, like the disks) of the info box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+    Distro,
+    DistroId,
+    Kernel,
+    Cpu,
+    Gpu,
+    Memory,
+    Swap,
+    LocalIp,
+    Battery,
+    Uptime,
+    Storage,
+}
+
+// name in the config, the module, and the label it gets unless the config renames it.
+// also the default order
+const MODULES: &[(&str, ModuleKind, &str)] = &[
+    ("distro", ModuleKind::Distro, "Distro"),
+    ("distro_id", ModuleKind::DistroId, "Distro ID"),
+    ("kernel", ModuleKind::Kernel, "Kernel"),
+    ("cpu", ModuleKind::Cpu, "CPU"),
+    ("gpu", ModuleKind::Gpu, "GPU"),
+    ("memory", ModuleKind::Memory, "Memory"),
+    ("swap", ModuleKind::Swap, "Swap"),
+    ("local_ip", ModuleKind::LocalIp, "Local IP"),
+    ("battery", ModuleKind::Battery, "Battery"),
+    ("uptime", ModuleKind::Uptime, "Uptime"),
+    ("storage", ModuleKind::Storage, "Disk"),
+];
+
+/// An entry of `modules`: just the name (`"cpu"`), or the name plus a label of its own
+/// (`{ type = "cpu", label = "Processor" }`). Names are checked in `validate` so a typo
+/// gets a proper message instead of failing the whole file.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Module {
+    Name(String),
+    Labeled(LabeledModule),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LabeledModule {
+    #[serde(rename = "type")]
+    pub name: String,
+    pub label: Option<String>,
+    // same as BoxChars, serde_ignored doesn't look inside untagged enums
+    #[serde(flatten, skip_serializing)]
+    pub unknown: BTreeMap<String, IgnoredAny>,
 }
 
+impl Module {
+    fn name(&self) -> &str {
+        match self {
+            Module::Name(name) | Module::Labeled(LabeledModule { name, .. }) => name,
+        }
+    }
+
+    fn kind(&self) -> Option<(ModuleKind, &'static str)> {
+        MODULES
+            .iter()
+            .find(|(name, _, _)| *name == self.name())
+            .map(|&(_, kind, label)| (kind, label))
+    }
+}
+
+/// The info box border: a preset name, or a table of your own characters. Both are checked
+/// in `validate` rather than by serde, so a typo is a warning instead of losing the whole file.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum BoxStyle {
+    Preset(String),
+    Custom(BoxChars),
+}
+
+impl BoxStyle {
+    /// The preset this names, None for custom borders and names that aren't presets
+    pub fn preset(&self) -> Option<BoxPreset> {
+        match self {
+            BoxStyle::Preset(name) => <BoxPreset as clap::ValueEnum>::from_str(name, false).ok(),
+            BoxStyle::Custom(_) => None,
+        }
+    }
+
+    fn validate(&self, problems: &mut Vec<String>) {
+        match self {
+            BoxStyle::Preset(name) if self.preset().is_none() => {
+                let names: Vec<String> = BoxPreset::value_variants()
+                    .iter()
+                    .filter_map(|preset| Some(preset.to_possible_value()?.get_name().to_string()))
+                    .collect();
+                problems.push(format!(
+                    "`box_style`: there is no style called \"{}\" (there's {})",
+                    name,
+                    names.join(", ")
+                ));
+            }
+            BoxStyle::Preset(_) => {}
+            BoxStyle::Custom(chars) => chars.validate(problems),
+        }
+    }
+}
+
+impl From<BoxPreset> for BoxStyle {
+    fn from(preset: BoxPreset) -> Self {
+        let name = preset.to_possible_value().map(|value| value.get_name().to_string());
+        BoxStyle::Preset(name.unwrap_or_default())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum BoxPreset {
+    /// ┌─┐ (the default)
+    Single,
+    /// ╭─╮
+    Rounded,
+    /// ╔═╗
+    Double,
+    /// ┏━┓
+    Heavy,
+    /// +-+, for consoles without box drawing characters
+    Ascii,
+    /// No border at all
+    None,
+}
+
+/// A hand-made border. Characters that are left out come from the "single" style.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct BoxChars {
+    pub top_left: Option<String>,
+    pub top_right: Option<String>,
+    pub bottom_left: Option<String>,
+    pub bottom_right: Option<String>,
+    pub horizontal: Option<String>,
+    pub vertical: Option<String>,
+    /// Where the line under the title meets the left and right sides (├ and ┤)
+    pub left_tee: Option<String>,
+    pub right_tee: Option<String>,
+    // serde_ignored can't see inside an untagged enum, so misspelled keys land here
+    #[serde(flatten, skip_serializing)]
+    pub unknown: BTreeMap<String, IgnoredAny>,
+}
+
+impl BoxChars {
+    fn validate(&self, problems: &mut Vec<String>) {
+        let chars = [
+            ("top_left", &self.top_left),
+            ("top_right", &self.top_right),
+            ("bottom_left", &self.bottom_left),
+            ("bottom_right", &self.bottom_right),
+            ("horizontal", &self.horizontal),
+            ("vertical", &self.vertical),
+            ("left_tee", &self.left_tee),
+            ("right_tee", &self.right_tee),
+        ];
+        for (name, value) in chars {
+            if let Some(value) = value
+                && border_char(value).is_none()
+            {
+                problems.push(format!("`box_style.{}`: \"{}\" has to be one character, a single column wide", name, value));
+            }
+        }
+        for key in self.unknown.keys() {
+            problems.push(format!("unknown key `box_style.{}`", key));
+        }
+    }
+}
+
+/// The character in `text` when it's exactly one and a single column wide. Anything wider or
+/// narrower would break the box apart.
+pub fn border_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    let ch = chars.next()?;
+    (chars.next().is_none() && ch.width() == Some(1)).then_some(ch)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Size {
+    Bytes(u64),
+    Text(String),
+}
+
+impl Size {
+    pub fn bytes(&self) -> Option<u64> {
+        match self {
+            Size::Bytes(bytes) => Some(*bytes),
+            Size::Text(text) => parse_size(text),
+        }
+    }
+}
+
+// "1024", "512MiB", "1.5 GB", "2g" -> bytes. the "i" units are powers of 1024, the
+// plain two-letter ones powers of 1000, and single letters are 1024 like df
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: f64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kib" => 1024.0,
+        "m" | "mib" => 1024f64.powi(2),
+        "g" | "gib" => 1024f64.powi(3),
+        "t" | "tib" => 1024f64.powi(4),
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+/// How memory, swap and disk sizes are printed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteUnits {
+    /// Always GiB (powers of 1024)
+    Iec,
+    /// Always GB (powers of 1000)
+    Si,
+    /// Scaled to the largest unit that keeps the number above 1 (512.00 MiB, 1.50 TiB)
+    Auto,
+}
+
+/// Which version of a logo to draw, for distros that ship more than one.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogoVariant {
+    /// The regular logo (arch.txt)
+    Default,
+    /// The compact one, for small terminals (arch_small.txt)
+    Small,
+    /// The alternative or older design (arch2.txt, arch_old.txt)
+    Alt,
+}
+
+/// Whether `logo_path` is a text logo or a picture.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogoType {
+    /// Text with $1..$9 color markers (all the built-in logos)
+    Ascii,
+    /// A PNG or JPEG, drawn with the terminal's graphics protocol
+    Image,
+}
+
+/// How an image logo gets to the terminal.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocol {
+    /// Guess from $TERM / $TERM_PROGRAM, half blocks when nothing better is around
+    Auto,
+    /// kitty graphics protocol (kitty, WezTerm, Ghostty, Konsole)
+    Kitty,
+    /// Sixel (foot, xterm -ti vt340, mlterm, contour)
+    Sixel,
+    /// iTerm2 inline images (iTerm2, WezTerm)
+    Iterm2,
+    /// Unicode half blocks in true color, works in any modern terminal
+    Blocks,
+    /// Colored ASCII characters picked by brightness, for everything else
+    Ascii,
+}
+
+pub const DEFAULT_IMAGE_WIDTH: u16 = 30;
+
 impl Default for Config {
     fn default() -> Self {
         Self {
-            show_distro: Some(true),
-            show_distro_id: Some(true),
-            show_kernel: Some(true),
-            show_cpu: Some(true),
-            show_gpu: Some(true),
-            show_memory: Some(true),
-            show_swap: Some(true),
-            show_local_ip: Some(true),
-            show_battery: Some(true),
-            show_storage: Some(true),
-            show_uptime: Some(true),
-            logo_color: Some("#00FFFF #FF00FF #FFFF00 #FFFFFF".to_string()), 
+            show_distro: None,
+            show_distro_id: None,
+            show_kernel: None,
+            show_cpu: None,
+            show_gpu: None,
+            show_gpu_stats: Some(false),
+            show_memory: None,
+            show_swap: None,
+            show_local_ip: None,
+            show_battery: None,
+            show_storage: None,
+            show_uptime: None,
+            logo_color: None,
             color: Some("#FFFFFF".to_string()),
             show_user_host: Some(true),
+            logo: None,
+            logo_variant: Some(LogoVariant::Default),
+            logo_path: None,
+            logo_type: Some(LogoType::Ascii),
+            image_protocol: Some(ImageProtocol::Auto),
+            image_width: Some(DEFAULT_IMAGE_WIDTH),
+            units: Some(ByteUnits::Auto),
+            precision: Some(2),
+            storage: Some(StorageConfig {
+                show_removable: Some(true),
+                ..Default::default()
+            }),
+            layout: Some(LayoutConfig {
+                logo_position: Some(LogoPosition::Left),
+                gap: Some(DEFAULT_GAP),
+                align: Some(VerticalAlign::Top),
+            }),
+            box_style: Some(BoxPreset::Single.into()),
+            modules: Some(MODULES.iter().map(|(name, _, _)| Module::Name(name.to_string())).collect()),
         }
     }
 }
 
+// json5 casts numbers straight into the field type ("precision": 300 turns into 255, -5 into 0
+// and 1.9 into 1), so the integer fields take the number as written and check it themselves.
+// errors raised in here still get json5's line and column
+fn checked_int<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<i64>,
+{
+    struct CheckedInt<T>(PhantomData<T>);
+
+    impl<'de, T: TryFrom<i64>> Visitor<'de> for CheckedInt<T> {
+        type Value = Option<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            // "u8", same as what the TOML parser says
+            f.write_str(std::any::type_name::<T>())
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+            T::try_from(value)
+                .map(Some)
+                .map_err(|_| E::invalid_value(Unexpected::Signed(value), &self))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+            i64::try_from(value)
+                .ok()
+                .and_then(|value| T::try_from(value).ok())
+                .map(Some)
+                .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self))
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+            Err(E::invalid_type(Unexpected::Float(value), &self))
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    deserializer.deserialize_any(CheckedInt(PhantomData))
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, line: usize, column: usize, message: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ConfigError::Parse { path, line, column, message } => {
+                write!(f, "{}:{}:{}: {}", path.display(), line, column, message)
+            }
+        }
+    }
+}
+
+/// Something odd in a config file that doesn't stop the rest of it from loading,
+/// like a misspelled key or a color that isn't a color.
+#[derive(Debug)]
+pub struct ConfigWarning {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: warning: {}", self.path.display(), self.message)
+    }
+}
+
+/// What happened while loading: every file that was looked at, plus anything wrong with them.
+#[derive(Debug, Default)]
+pub struct ConfigReport {
+    pub files: Vec<PathBuf>,
+    pub errors: Vec<ConfigError>,
+    pub warnings: Vec<ConfigWarning>,
+}
+
+impl ConfigReport {
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
 impl Config {
-    #[allow(dead_code)]
-    pub fn from_file(path: &str) -> Option<Self> {
-        let content = std::fs::read_to_string(path).ok()?;
-        serde_json::from_str(&content).ok()
-    }
-
-    pub fn from_exe_dir() -> Option<Self> {
-        use std::env;
-        use std::path::PathBuf;
-        let exe_path = env::current_exe().ok()?;
-        let exe_dir = exe_path.parent()?;
-        let config_path: PathBuf = exe_dir.join("config.json");
-        let content = std::fs::read_to_string(config_path).ok()?;
-        serde_json::from_str(&content).ok()
-    }
-}
\ No newline at end of file
+    /// Reads one config file. `.toml` files are parsed as TOML, anything else as JSON5,
+    /// which is plain JSON plus comments and trailing commas. Unknown keys and invalid
+    /// values come back as warnings rather than failing the whole file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<(Self, Vec<ConfigWarning>), ConfigError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let mut unknown_keys = Vec::new();
+        let mut config: Config = if is_toml(path) {
+            let de = toml::Deserializer::new(&content);
+            serde_ignored::deserialize(de, |key| unknown_keys.push(key_name(&key))).map_err(|e| {
+                let (line, column) = e
+                    .span()
+                    .map(|span| line_col(&content, span.start))
+                    .unwrap_or((1, 1));
+                ConfigError::Parse {
+                    path: path.to_path_buf(),
+                    line,
+                    column,
+                    message: e.message().to_string(),
+                }
+            })?
+        } else {
+            json5::Deserializer::from_str(&content)
+                .and_then(|mut de| {
+                    serde_ignored::deserialize(&mut de, |key| unknown_keys.push(key_name(&key)))
+                })
+                .map_err(|e| {
+                    let json5::Error::Message { msg, location } = e;
+                    let (line, column) = location.map(|l| (l.line, l.column)).unwrap_or((1, 1));
+                    ConfigError::Parse {
+                        path: path.to_path_buf(),
+                        line,
+                        column,
+                        message: short_json5_message(&msg),
+                    }
+                })?
+        };
+
+        // a relative logo_path means next to the config file, not wherever zfetch was started
+        if let Some(logo_path) = &mut config.logo_path {
+            let expanded = expand_home(logo_path);
+            *logo_path = match path.parent() {
+                Some(dir) if expanded.is_relative() => dir.join(expanded),
+                _ => expanded,
+            };
+        }
+
+        let warnings = unknown_keys
+            .into_iter()
+            .map(|key| format!("unknown key `{}`", key))
+            .chain(config.validate())
+            .map(|message| ConfigWarning { path: path.to_path_buf(), message })
+            .collect();
+        Ok((config, warnings))
+    }
+
+    /// Builds the effective config: built-in defaults, then every config file found by
+    /// `config_paths` from lowest to highest priority, each overriding only the keys it sets.
+    /// Files that fail to load are skipped and show up in the report instead.
+    pub fn load(explicit: Option<&Path>) -> (Self, ConfigReport) {
+        let mut config = Config::default();
+        let mut report = ConfigReport::default();
+        // the file that turned image logos on, that's where a missing logo_path gets reported
+        let mut logo_type_from = None;
+        for path in config_paths(explicit) {
+            match Config::from_file(&path) {
+                Ok((layer, warnings)) => {
+                    if layer.logo_type.is_some() {
+                        logo_type_from = Some(path.clone());
+                    }
+                    config.merge(layer);
+                    report.warnings.extend(warnings);
+                }
+                Err(err) => report.errors.push(err),
+            }
+            report.files.push(path);
+        }
+        // settings that only make sense together can be spread over several files, so they
+        // are checked once everything is merged
+        if let Some(path) = logo_type_from
+            && config.logo_type == Some(LogoType::Image)
+            && config.logo_path.is_none()
+        {
+            report.warnings.push(ConfigWarning {
+                path,
+                message: "`logo_type`: \"image\" needs `logo_path` to point at a picture".to_string(),
+            });
+        }
+        (config, report)
+    }
+
+    /// Checks the values that parsed fine but can't actually be used.
+    /// Returns one human-readable message per problem.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(color) = &self.color
+            && parse_hex_color(color).is_none()
+        {
+            problems.push(format!("`color`: \"{}\" is not a hex color like \"#FF5733\"", color));
+        }
+        if let Some(logo_color) = &self.logo_color {
+            let colors = split_colors(logo_color);
+            for color in &colors {
+                if parse_hex_color(color).is_none() {
+                    problems.push(format!("`logo_color`: \"{}\" is not a hex color like \"#FF5733\"", color));
+                }
+            }
+            if colors.len() > 9 {
+                problems.push(format!(
+                    "`logo_color`: logos only use $1 to $9, but {} colors were given",
+                    colors.len()
+                ));
+            }
+        }
+        if let Some(logo) = &self.logo
+            && !logo_exists(logo)
+        {
+            problems.push(format!("`logo`: there is no logo called \"{}\"", logo));
+        }
+        if let Some(path) = &self.logo_path
+            && !expand_home(path).is_file()
+        {
+            problems.push(format!("`logo_path`: {} doesn't exist", path.display()));
+        }
+        if self.image_width == Some(0) {
+            problems.push("`image_width`: has to be at least 1".to_string());
+        }
+        if let Some(precision) = self.precision
+            && precision > MAX_PRECISION
+        {
+            problems.push(format!(
+                "`precision`: {} is out of range, it can be at most {}",
+                precision, MAX_PRECISION
+            ));
+        }
+        // a module listed twice draws twice, which is never what anybody meant
+        let mut seen: Vec<ModuleKind> = Vec::new();
+        let mut repeated: Vec<ModuleKind> = Vec::new();
+        for module in self.modules.iter().flatten() {
+            if let Some((kind, _)) = module.kind() {
+                if seen.contains(&kind) && !repeated.contains(&kind) {
+                    problems.push(format!("`modules`: \"{}\" is listed more than once", module.name()));
+                    repeated.push(kind);
+                }
+                seen.push(kind);
+            }
+            if let Module::Labeled(labeled) = module {
+                for key in labeled.unknown.keys() {
+                    problems.push(format!("unknown key `modules.{}` (entries take `type` and `label`)", key));
+                }
+            }
+            if module.kind().is_none() {
+                let names: Vec<&str> = MODULES.iter().map(|(name, _, _)| *name).collect();
+                problems.push(format!(
+                    "`modules`: there is no module called \"{}\" (there's {})",
+                    module.name(),
+                    names.join(", ")
+                ));
+            }
+        }
+        // show_cpu and friends are what modules replaced. they keep working so older configs
+        // and --no-cpu still do something, but next to a modules list they just confuse
+        if self.modules.is_some() {
+            for &(name, kind, _) in MODULES {
+                if self.switch(kind).is_some() {
+                    problems.push(format!(
+                        "`show_{}` is deprecated, add or leave out \"{}\" in `modules` instead",
+                        name, name
+                    ));
+                }
+            }
+        }
+        if let Some(box_style) = &self.box_style {
+            box_style.validate(&mut problems);
+        }
+        if let Some(storage) = &self.storage {
+            storage.validate(&mut problems);
+        }
+        problems
+    }
+
+    /// The rows to draw, in order, with their labels. A module whose (deprecated) `show_*`
+    /// switch is off, in an older config or with `--no-<name>`, is left out even when it's listed.
+    pub fn modules(&self) -> Vec<(ModuleKind, String)> {
+        let listed: Vec<(ModuleKind, String)> = match &self.modules {
+            Some(modules) => modules
+                .iter()
+                .filter_map(|module| {
+                    let (kind, default_label) = module.kind()?;
+                    let label = match module {
+                        Module::Labeled(LabeledModule { label: Some(label), .. }) => label.clone(),
+                        _ => default_label.to_string(),
+                    };
+                    Some((kind, label))
+                })
+                .collect(),
+            None => MODULES.iter().map(|&(_, kind, label)| (kind, label.to_string())).collect(),
+        };
+        listed.into_iter().filter(|(kind, _)| self.switch(*kind) != Some(false)).collect()
+    }
+
+    /// Whether `kind` ends up in the box, so detection can skip whatever isn't shown.
+    pub fn shows(&self, kind: ModuleKind) -> bool {
+        self.modules().iter().any(|(shown, _)| *shown == kind)
+    }
+
+    fn switch(&self, kind: ModuleKind) -> Option<bool> {
+        match kind {
+            ModuleKind::Distro => self.show_distro,
+            ModuleKind::DistroId => self.show_distro_id,
+            ModuleKind::Kernel => self.show_kernel,
+            ModuleKind::Cpu => self.show_cpu,
+            ModuleKind::Gpu => self.show_gpu,
+            ModuleKind::Memory => self.show_memory,
+            ModuleKind::Swap => self.show_swap,
+            ModuleKind::LocalIp => self.show_local_ip,
+            ModuleKind::Battery => self.show_battery,
+            ModuleKind::Uptime => self.show_uptime,
+            ModuleKind::Storage => self.show_storage,
+        }
+    }
+
+    pub fn merge(&mut self, other: Config) {
+        merge_fields!(
+            self, other,
+            show_distro,
+            show_distro_id,
+            show_kernel,
+            show_cpu,
+            show_gpu,
+            show_gpu_stats,
+            show_memory,
+            show_swap,
+            show_local_ip,
+            show_battery,
+            show_storage,
+            show_uptime,
+            logo_color,
+            color,
+            show_user_host,
+            logo,
+            logo_variant,
+            logo_path,
+            logo_type,
+            image_protocol,
+            image_width,
+            units,
+            precision,
+            box_style,
+            modules,
+        );
+        if let Some(other) = other.storage {
+            match &mut self.storage {
+                Some(storage) => storage.merge(other),
+                None => self.storage = Some(other),
+            }
+        }
+        if let Some(other) = other.layout {
+            match &mut self.layout {
+                Some(layout) => layout.merge(other),
+                None => self.layout = Some(other),
+            }
+        }
+    }
+}
+
+pub const MAX_PRECISION: u8 = 6;
+
+// what --gen-config writes next to each key, in file order. Keys that default to
+// unset get their example written as a commented-out line instead.
+const OPTION_DOCS: &[(&str, &str, Option<&str>)] = &[
+    ("modules", "Rows of the info box, in this order. An entry is a module name or { type = \"cpu\", label = \"Processor\" } to rename it", None),
+    ("show_gpu_stats", "Show live GPU temperature, load and clock under each GPU (Linux, reads sysfs)", None),
+    ("show_user_host", "Put user@host in the box title", None),
+    ("color", "Color of the info box, as #RRGGBB", None),
+    ("box_style", "Border of the info box: \"single\", \"rounded\", \"double\", \"heavy\", \"ascii\" or \"none\" (or a [box_style] table of characters, see the README)", None),
+    ("logo_color", "Colors for the logo's $1..$9 markers, comma or space separated (unset: the logo's own colors)", Some("\"#1793D1 #FFFFFF\"")),
+    ("logo", "Logo to show instead of the detected distro's (`zfetch --list-logos` shows them all)", Some("\"arch\"")),
+    ("logo_variant", "\"default\", \"small\" or \"alt\", falls back to the default logo when a distro has no such variant", None),
+    ("logo_path", "Draw this file instead of a built-in logo (same $1..$9 color markers), relative paths start next to this config file", Some("\"~/.config/zfetch/my-logo.txt\"")),
+    ("logo_type", "\"ascii\" or \"image\" (then logo_path is a PNG/JPEG)", None),
+    ("image_protocol", "How to draw image logos: \"auto\", \"kitty\", \"sixel\", \"iterm2\", \"blocks\" or \"ascii\"", None),
+    ("image_width", "Width of an image logo in columns", None),
+    ("units", "Size units: \"iec\" (always GiB), \"si\" (always GB) or \"auto\" (MiB, GiB, TiB, whichever fits)", None),
+    ("precision", "Decimal places for memory, swap and disk sizes (0 to 6)", None),
+    ("storage.include_mounts", "Only show mounts matching one of these globs", Some("[\"/\", \"/home\", \"/mnt/*\"]")),
+    ("storage.exclude_mounts", "Never show mounts matching these globs", Some("[\"/boot/*\"]")),
+    ("storage.exclude_fs_types", "Never show these filesystem types", Some("[\"vfat\", \"nfs\"]")),
+    ("storage.min_size", "Hide filesystems smaller than this (bytes, or a string like \"1 GiB\")", Some("\"1 GiB\"")),
+    ("storage.show_removable", "Show USB sticks and other removable drives", None),
+    ("layout.logo_position", "\"left\", \"right\", \"top\", \"bottom\" or \"none\" for just the info box", None),
+    ("layout.gap", "Columns between the logo and the box", None),
+    ("layout.align", "\"top\" or \"center\": where the shorter of logo and box sits next to the other", None),
+];
+
+/// Writes `Config::default()` to `path` with every option documented inline.
+/// `.toml` paths get TOML, anything else gets JSON with `//` comments (which ZFetch reads fine).
+pub fn write_default_config(path: &Path, force: bool) -> std::io::Result<()> {
+    if path.exists() && !force {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists (use --force to overwrite it)", path.display()),
+        ));
+    }
+    // only one config file per directory gets read, so a config.toml next to an existing
+    // config.json would quietly take its place (or the other way round, be ignored)
+    let is_config_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| CONFIG_FILE_NAMES.contains(&name));
+    if is_config_name
+        && !force
+        && let Some(existing) = CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| path.with_file_name(name))
+            .find(|existing| existing != path && existing.is_file())
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists and only one config file per directory is read (use --force to write {} anyway)",
+                existing.display(),
+                path.display()
+            ),
+        ));
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, default_config_text(is_toml(path)))
+}
+
+fn default_config_text(toml: bool) -> String {
+    let defaults = serde_json::to_value(Config::default()).unwrap_or_default();
+    let comment = if toml { "#" } else { "//" };
+
+    let mut out = String::new();
+    out.push_str(&format!("{} ZFetch config, generated by `zfetch --gen-config`.\n", comment));
+    out.push_str(&format!("{} Delete whatever you don't need, missing keys use the built-in defaults.\n\n", comment));
+    if !toml {
+        out.push_str("{\n");
+    }
+    // "storage.min_size" lives in a [storage] table / "storage": {} object
+    let mut section: Option<&str> = None;
+    for (i, (key, doc, example)) in OPTION_DOCS.iter().enumerate() {
+        let (key_section, name) = match key.split_once('.') {
+            Some((section, name)) => (Some(section), name),
+            None => (None, *key),
+        };
+        if key_section != section {
+            if !toml && section.is_some() {
+                out.push_str("  },\n");
+            }
+            if let Some(new_section) = key_section {
+                if toml {
+                    out.push_str(&format!("\n[{}]\n", new_section));
+                } else {
+                    out.push_str(&format!("\n  \"{}\": {{\n", new_section));
+                }
+            }
+            section = key_section;
+        } else if i > 0 {
+            out.push('\n');
+        }
+
+        let indent = match (toml, section) {
+            (true, _) => "",
+            (false, None) => "  ",
+            (false, Some(_)) => "    ",
+        };
+        out.push_str(&format!("{}{} {}\n", indent, comment, doc));
+        // serde_json's formatting of strings, numbers, bools and flat arrays is valid TOML too
+        let default = key
+            .split('.')
+            .try_fold(&defaults, |value, part| value.get(part))
+            .filter(|v| !v.is_null());
+        let (prefix, value) = match default {
+            Some(value) => (String::new(), value.to_string()),
+            None => match example {
+                Some(example) => (format!("{} ", comment), example.to_string()),
+                None => continue,
+            },
+        };
+        if toml {
+            out.push_str(&format!("{}{} = {}\n", prefix, name, value));
+        } else {
+            out.push_str(&format!("{}{}\"{}\": {},\n", indent, prefix, name, value));
+        }
+    }
+    if !toml {
+        if section.is_some() {
+            out.push_str("  },\n");
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// Per-user config directory: `$XDG_CONFIG_HOME/zfetch`, falling back to `~/.config/zfetch`
+/// (`%APPDATA%\zfetch` on Windows).
+pub fn user_config_dir() -> Option<PathBuf> {
+    if let Some(xdg) = env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(xdg).join("zfetch"));
+    }
+    if cfg!(target_os = "windows")
+        && let Some(appdata) = env::var_os("APPDATA")
+    {
+        return Some(PathBuf::from(appdata).join("zfetch"));
+    }
+    home_dir().map(|home| home.join(".config").join("zfetch"))
+}
+
+/// "~/logo.txt" -> "/home/me/logo.txt", anything else is left alone.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+const CONFIG_FILE_NAMES: [&str; 3] = ["config.toml", "config.json5", "config.json"];
+
+/// Every place a config file may live, lowest priority first:
+///
+/// 1. `/etc/zfetch/` (site-wide defaults)
+/// 2. next to the executable (the old location, still honoured)
+/// 3. `~/.config/zfetch/`
+/// 4. `$XDG_CONFIG_HOME/zfetch/` when it points somewhere else
+/// 5. the file named by `$ZFETCH_CONFIG`
+/// 6. the file passed with `--config`
+///
+/// Each directory contributes its `config.toml`, `config.json5` or `config.json`,
+/// whichever is found first. Directories with none of them are skipped.
+pub fn config_paths(explicit: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if cfg!(unix) {
+        dirs.push(PathBuf::from("/etc/zfetch"));
+    } else if let Some(program_data) = env::var_os("PROGRAMDATA") {
+        dirs.push(PathBuf::from(program_data).join("zfetch"));
+    }
+    if let Some(exe_dir) = env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        dirs.push(exe_dir);
+    }
+    if let Some(home) = home_dir() {
+        dirs.push(home.join(".config").join("zfetch"));
+    }
+    if let Some(user_dir) = user_config_dir() {
+        dirs.push(user_dir);
+    }
+
+    let mut found: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        // one file per directory; TOML wins if someone has both lying around
+        let file = CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|file| file.is_file());
+        if let Some(file) = file
+            && !found.contains(&file)
+        {
+            found.push(file);
+        }
+    }
+
+    // files named explicitly are returned even if missing, so a typo gets reported
+    // instead of quietly ignored
+    if let Some(env_path) = env::var_os("ZFETCH_CONFIG").filter(|v| !v.is_empty()) {
+        found.push(PathBuf::from(env_path));
+    }
+    if let Some(path) = explicit {
+        found.push(path.to_path_buf());
+    }
+    found
+}
+
+// "storage.exclude_mount" for serde_ignored's storage -> Some -> exclude_mount, its own
+// Display writes the Option in between as "storage.?.exclude_mount"
+fn key_name(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path as P;
+    match path {
+        P::Root => String::new(),
+        P::Some { parent } | P::NewtypeStruct { parent } | P::NewtypeVariant { parent } => key_name(parent),
+        P::Seq { parent, index } => join_key(key_name(parent), &index.to_string()),
+        P::Map { parent, key } => join_key(key_name(parent), key),
+    }
+}
+
+fn join_key(parent: String, key: &str) -> String {
+    if parent.is_empty() { key.to_string() } else { format!("{}.{}", parent, key) }
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+// 1-based line and column (in chars) of a byte offset
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+// json5 hands back pest's multi-line error report, we already print the position
+// ourselves so just keep the "expected ..." part
+fn short_json5_message(msg: &str) -> String {
+    msg.lines()
+        .find_map(|line| line.trim_start().strip_prefix("= "))
+        .unwrap_or(msg)
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // from_file wants a real file, the extension picks the format
+    fn load(name: &str, content: &str) -> Result<(Config, Vec<ConfigWarning>), ConfigError> {
+        let path = env::temp_dir().join(format!("zfetch-test-{}-{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        let result = Config::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    fn parse_error(name: &str, content: &str) -> (usize, usize, String) {
+        match load(name, content) {
+            Err(ConfigError::Parse { line, column, message, .. }) => (line, column, message),
+            other => panic!("expected a parse error, got {:?}", other.map(|(config, _)| config)),
+        }
+    }
+
+    #[test]
+    fn json_numbers_are_not_clamped() {
+        let (line, column, message) = parse_error("clamp.json", "{\n  \"precision\": 300\n}");
+        assert_eq!((line, column), (2, 16));
+        assert!(message.contains("300"), "{}", message);
+
+        let (line, _, message) = parse_error("negative.json", "{\n  \"image_width\": -5\n}");
+        assert_eq!(line, 2);
+        assert!(message.contains("-5"), "{}", message);
+
+        let (line, _, message) = parse_error("fraction.json5", "{\n  layout: {\n    gap: 1.9,\n  },\n}");
+        assert_eq!(line, 3);
+        assert!(message.contains("1.9"), "{}", message);
+
+        let (config, warnings) = load("fine.json", "{ \"precision\": 3, \"image_width\": null }").unwrap();
+        assert_eq!(config.precision, Some(3));
+        assert_eq!(config.image_width, None);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("512MiB"), Some(512 << 20));
+        assert_eq!(parse_size("1.5 GB"), Some(1_500_000_000));
+        assert_eq!(parse_size("2g"), Some(2 << 30));
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size(" 3 kb "), Some(3000));
+        assert_eq!(parse_size("5 parsecs"), None);
+        assert_eq!(parse_size("GiB"), None);
+        assert_eq!(parse_size(""), None);
+    }
+
+    #[test]
+    fn unknown_keys_in_sections() {
+        let toml = "colour = \"#FFFFFF\"\n[storage]\nexclude_mount = [\"/boot\"]\n[layout]\ngapp = 3\n";
+        let (_, warnings) = load("keys.toml", toml).unwrap();
+        let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["unknown key `colour`", "unknown key `storage.exclude_mount`", "unknown key `layout.gapp`"]
+        );
+
+        let (_, warnings) = load("keys.json", "{ \"storage\": { \"min_sise\": 1 } }").unwrap();
+        assert_eq!(warnings[0].message, "unknown key `storage.min_sise`");
+    }
+
+    #[test]
+    fn generated_config_loads_back_as_the_defaults() {
+        let defaults = serde_json::to_value(Config::default()).unwrap();
+        for (name, toml) in [("gen.toml", true), ("gen.json", false)] {
+            let (config, warnings) = load(name, &default_config_text(toml)).unwrap();
+            let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+            assert!(messages.is_empty(), "{}: {:?}", name, messages);
+            assert_eq!(serde_json::to_value(config).unwrap(), defaults, "{}", name);
+        }
+    }
+
+    #[test]
+    fn merge_keeps_what_the_other_side_leaves_unset() {
+        let mut config = Config::default();
+        let (file, _) = load("merge.toml", "precision = 4\n[storage]\nmin_size = \"1 GiB\"\n[layout]\ngap = 5\n").unwrap();
+        config.merge(file);
+
+        assert_eq!(config.precision, Some(4));
+        assert_eq!(config.color.as_deref(), Some("#FFFFFF"));
+        let storage = config.storage.as_ref().unwrap();
+        assert_eq!(storage.show_removable, Some(true));
+        assert!(storage.min_size.is_some());
+        let layout = config.layout.as_ref().unwrap();
+        assert_eq!(layout.gap, Some(5));
+        assert_eq!(layout.logo_position, Some(LogoPosition::Left));
+    }
+
+    #[test]
+    fn line_and_column() {
+        let text = "ab\ncdé\nf";
+        assert_eq!(line_col(text, 0), (1, 1));
+        assert_eq!(line_col(text, 2), (1, 3));
+        assert_eq!(line_col(text, 3), (2, 1));
+        // columns count characters, not bytes
+        assert_eq!(line_col(text, 8), (3, 1));
+        assert_eq!(line_col(text, 7), (2, 4));
+        assert_eq!(line_col(text, 100), (3, 2));
+    }
+
+    #[test]
+    fn module_order_labels_and_switches() {
+        let config = Config::default();
+        let names: Vec<ModuleKind> = config.modules().into_iter().map(|(kind, _)| kind).collect();
+        let all: Vec<ModuleKind> = MODULES.iter().map(|&(_, kind, _)| kind).collect();
+        assert_eq!(names, all);
+
+        let toml = "modules = [\"uptime\", { type = \"cpu\", label = \"Processor\" }, \"memory\", \"nope\"]\nshow_memory = false\n";
+        let (config, warnings) = load("modules.toml", toml).unwrap();
+        assert_eq!(
+            config.modules(),
+            [(ModuleKind::Uptime, "Uptime".to_string()), (ModuleKind::Cpu, "Processor".to_string())]
+        );
+        assert!(config.shows(ModuleKind::Cpu));
+        assert!(!config.shows(ModuleKind::Memory));
+        assert!(warnings.iter().any(|w| w.message.contains("nope")));
+
+        let toml = "modules = [\"cpu\", \"kernel\", { type = \"cpu\", label = \"Again\" }, \"cpu\"]\n";
+        let (_, warnings) = load("twice.toml", toml).unwrap();
+        let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(messages, ["`modules`: \"cpu\" is listed more than once"]);
+    }
+}
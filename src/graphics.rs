@@ -0,0 +1,283 @@
+use crate::config::{Config, ImageProtocol, DEFAULT_IMAGE_WIDTH};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::env;
+use std::io::Cursor;
+use std::path::Path;
+
+// what a terminal cell measures when the tty won't tell us, most fonts are about 1:2
+const FALLBACK_CELL_SIZE: (u32, u32) = (10, 20);
+
+// darkest to brightest, for terminals that can't even do half blocks
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// What a picture turned into: something the terminal draws itself, or plain colored text.
+pub enum PictureLogo {
+    Graphics(ImageLogo),
+    /// Same as the text logos, one line per row, already colored
+    Text(String),
+}
+
+/// A picture ready to print, plus how many cells it covers so the info box can be laid out
+/// next to it exactly like a text logo.
+pub struct ImageLogo {
+    pub escape: String,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl ImageLogo {
+    /// Blank text the size of the picture, so the layout reserves room for it
+    pub fn placeholder(&self) -> String {
+        vec![" ".repeat(self.columns); self.rows].join("\n")
+    }
+}
+
+/// Loads `path` and encodes it for the terminal, `image_width` cells wide. Terminals without
+/// a graphics protocol get it drawn with half blocks instead.
+pub fn load_image_logo(path: &Path, config: &Config) -> Result<PictureLogo, String> {
+    let protocol = match config.image_protocol.unwrap_or(ImageProtocol::Auto) {
+        ImageProtocol::Auto => detect_protocol().unwrap_or(ImageProtocol::Blocks),
+        protocol => protocol,
+    };
+    let picture = image::open(path).map_err(|err| format!("couldn't load {}: {}", path.display(), err))?;
+    let columns = config.image_width.unwrap_or(DEFAULT_IMAGE_WIDTH).max(1) as usize;
+
+    match protocol {
+        ImageProtocol::Blocks => return Ok(PictureLogo::Text(half_blocks(&picture, columns))),
+        ImageProtocol::Ascii => return Ok(PictureLogo::Text(ascii_art(&picture, columns))),
+        _ => {}
+    }
+
+    // keep the aspect ratio, the width in cells decides everything else
+    let (cell_width, cell_height) = cell_size();
+    let pixel_width = columns as u32 * cell_width;
+    let pixel_height = scaled_height(&picture, pixel_width);
+    let rows = pixel_height.div_ceil(cell_height).max(1) as usize;
+    // no point shipping a 4000px photo to the terminal just to have it scaled down there
+    let picture = picture.resize_exact(pixel_width, pixel_height, FilterType::Triangle);
+
+    let escape = match protocol {
+        ImageProtocol::Kitty => kitty(&picture, columns, rows)?,
+        ImageProtocol::Iterm2 => iterm2(&picture, columns, rows)?,
+        _ => sixel(&picture.to_rgba8()),
+    };
+    Ok(PictureLogo::Graphics(ImageLogo { escape, columns, rows }))
+}
+
+// height that keeps the aspect ratio at `width`
+fn scaled_height(picture: &DynamicImage, width: u32) -> u32 {
+    ((picture.height() as u64 * width as u64) / picture.width().max(1) as u64).max(1) as u32
+}
+
+// '▀' with the top pixel as foreground and the bottom one as background, two pixels a cell.
+// a cell is about twice as tall as it's wide, so that keeps the pixels square
+fn half_blocks(picture: &DynamicImage, columns: usize) -> String {
+    let width = columns as u32;
+    let height = scaled_height(picture, width).next_multiple_of(2);
+    let pixels = picture.resize_exact(width, height, FilterType::Triangle).to_rgba8();
+
+    let mut lines = Vec::new();
+    for y in (0..height).step_by(2) {
+        let mut line = String::new();
+        for x in 0..width {
+            let top = pixels.get_pixel(x, y).0;
+            let bottom = pixels.get_pixel(x, y + 1).0;
+            // transparent halves stay the terminal's own background
+            match (top[3] >= 128, bottom[3] >= 128) {
+                (true, true) => line.push_str(&format!(
+                    "\x1b[38;2;{};{};{};48;2;{};{};{}m▀",
+                    top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]
+                )),
+                (true, false) => line.push_str(&format!("\x1b[49;38;2;{};{};{}m▀", top[0], top[1], top[2])),
+                (false, true) => line.push_str(&format!("\x1b[49;38;2;{};{};{}m▄", bottom[0], bottom[1], bottom[2])),
+                (false, false) => line.push_str("\x1b[0m "),
+            }
+        }
+        line.push_str("\x1b[0m");
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+// one character a cell, picked from the ramp by brightness and colored like the pixel
+fn ascii_art(picture: &DynamicImage, columns: usize) -> String {
+    let width = columns as u32;
+    // half as many rows as columns per pixel, since cells are twice as tall as wide
+    let height = (scaled_height(picture, width) / 2).max(1);
+    let pixels = picture.resize_exact(width, height, FilterType::Triangle).to_rgba8();
+
+    let mut lines = Vec::new();
+    for y in 0..height {
+        let mut line = String::new();
+        for x in 0..width {
+            let [r, g, b, a] = pixels.get_pixel(x, y).0;
+            if a < 128 {
+                line.push(' ');
+                continue;
+            }
+            let luma = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
+            let ch = ASCII_RAMP[((luma * (ASCII_RAMP.len() - 1) as f32).round() as usize).min(ASCII_RAMP.len() - 1)];
+            line.push_str(&format!("\x1b[38;2;{};{};{}m{}", r, g, b, ch as char));
+        }
+        line.push_str("\x1b[0m");
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+// going by what the terminals advertise about themselves, there's no portable query
+fn detect_protocol() -> Option<ImageProtocol> {
+    let term = env::var("TERM").unwrap_or_default();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    if env::var_os("KITTY_WINDOW_ID").is_some()
+        || term == "xterm-kitty"
+        || term == "xterm-ghostty"
+        || matches!(program.as_str(), "WezTerm" | "ghostty")
+        || env::var_os("KONSOLE_VERSION").is_some()
+    {
+        Some(ImageProtocol::Kitty)
+    } else if program == "iTerm.app" || env::var_os("ITERM_SESSION_ID").is_some() {
+        Some(ImageProtocol::Iterm2)
+    } else if term.starts_with("foot") || term.contains("sixel") || term == "mlterm" || program == "contour" {
+        Some(ImageProtocol::Sixel)
+    } else {
+        None
+    }
+}
+
+// pixel size of one cell, from the tty when it reports it (kitty, foot, wezterm, xterm do)
+fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns).max(1) as u32,
+            (size.height / size.rows).max(1) as u32,
+        ),
+        _ => FALLBACK_CELL_SIZE,
+    }
+}
+
+fn png_bytes(picture: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    picture
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|err| format!("couldn't encode the logo: {}", err))?;
+    Ok(bytes)
+}
+
+// kitty graphics protocol: base64 PNG in chunks of at most 4096 bytes. C=1 keeps the cursor
+// where it is and q=2 stops the terminal from answering into our stdin
+fn kitty(picture: &DynamicImage, columns: usize, rows: usize) -> Result<String, String> {
+    let data = BASE64.encode(png_bytes(picture)?);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            out.push_str(&format!("\x1b_Ga=T,f=100,c={},r={},C=1,q=2,m={};{}\x1b\\", columns, rows, more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    Ok(out)
+}
+
+// iTerm2 inline images (also understood by WezTerm and VS Code's terminal)
+fn iterm2(picture: &DynamicImage, columns: usize, rows: usize) -> Result<String, String> {
+    let png = png_bytes(picture)?;
+    Ok(format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        columns,
+        rows,
+        BASE64.encode(&png)
+    ))
+}
+
+// sixel: the picture in bands of 6 pixel rows, one pass per color. Colors are snapped to a
+// 6x6x6 cube, plenty for a logo, and fully transparent pixels are just never painted
+fn sixel(picture: &RgbaImage) -> String {
+    let (width, height) = picture.dimensions();
+    let index: Vec<Option<u8>> = picture
+        .pixels()
+        .map(|p| {
+            let [r, g, b, a] = p.0;
+            let level = |v: u8| (v as u16 * 5 + 127) / 255;
+            (a >= 128).then(|| (level(r) * 36 + level(g) * 6 + level(b)) as u8)
+        })
+        .collect();
+
+    // P2=1: unpainted pixels keep the terminal background
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    let mut used = [false; 216];
+    for &color in index.iter().flatten() {
+        used[color as usize] = true;
+    }
+    for (color, _) in used.iter().enumerate().filter(|(_, used)| **used) {
+        let percent = |level: usize| level * 100 / 5;
+        out.push_str(&format!(
+            "#{};2;{};{};{}",
+            color,
+            percent(color / 36),
+            percent(color / 6 % 6),
+            percent(color % 6)
+        ));
+    }
+
+    for band in (0..height).step_by(6) {
+        let band_rows = (height - band).min(6);
+        let mut colors: Vec<u8> = (0..band_rows)
+            .flat_map(|dy| {
+                let row = ((band + dy) * width) as usize;
+                index[row..row + width as usize].iter().flatten().copied()
+            })
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for color in colors {
+            out.push_str(&format!("#{}", color));
+            let mut run: Option<(u8, usize)> = None;
+            for x in 0..width {
+                let mut bits = 0u8;
+                for dy in 0..band_rows {
+                    if index[((band + dy) * width + x) as usize] == Some(color) {
+                        bits |= 1 << dy;
+                    }
+                }
+                let sixel = 63 + bits;
+                run = match run {
+                    Some((current, count)) if current == sixel => Some((current, count + 1)),
+                    Some((current, count)) => {
+                        push_run(&mut out, current, count);
+                        Some((sixel, 1))
+                    }
+                    None => Some((sixel, 1)),
+                };
+            }
+            // nothing painted until the end of the row, '$' skips that anyway
+            if let Some((current, count)) = run.filter(|(current, _)| *current != b'?') {
+                push_run(&mut out, current, count);
+            }
+            // back to the start of the band for the next color
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+// "!12~" is 12 times '~', only worth it past a few repeats
+fn push_run(out: &mut String, sixel: u8, count: usize) {
+    if count > 3 {
+        out.push_str(&format!("!{}{}", count, sixel as char));
+    } else {
+        for _ in 0..count {
+            out.push(sixel as char);
+        }
+    }
+}
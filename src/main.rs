@@ -2,23 +2,164 @@ mod ascii;
 mod system;
 mod ui;
 mod config;
+mod cli;
+mod pci;
+mod graphics;
 
-use ascii::{get_ascii_logo, process_logo_colors};
-use system::get_system_info;
-use ui::display_output;
-use config::Config;
+use ascii::{get_ascii_logo, logo_names, logo_variant, process_logo_colors, resolve_logo};
+use system::{get_system_info, SystemInfo};
+use ui::{display_output, Logo};
+use config::{Config, ConfigReport, LogoType, LogoVariant};
+use graphics::{load_image_logo, PictureLogo};
+use cli::{Cli, Format};
+use clap::Parser;
+use std::path::Path;
 // use std::time::Instant;
 
 #[tokio::main]
 async fn main() {
     // let start = Instant::now();
-    let config = Config::from_exe_dir().unwrap_or_default();
+    let cli = Cli::parse();
+    if let Some(path) = &cli.gen_config {
+        std::process::exit(gen_config(path.as_deref(), cli.force));
+    }
+    if cli.list_logos {
+        for name in logo_names() {
+            println!("{}", name);
+        }
+        return;
+    }
+    let (mut config, report) = Config::load(cli.config.as_deref());
+    if cli.check_config {
+        std::process::exit(check_config(&report));
+    }
+    for err in &report.errors {
+        eprintln!("zfetch: {}", err);
+    }
+    for warning in &report.warnings {
+        eprintln!("zfetch: {}", warning);
+    }
+    cli.apply(&mut config);
+    // Config::load already checked this for the config files
+    if cli.logo_type == Some(LogoType::Image) && config.logo_path.is_none() {
+        eprintln!("zfetch: --logo-type image needs a picture in --logo-path or logo_path, drawing the text logo instead");
+    }
     let info = get_system_info(&config).await;
-    let logo = get_ascii_logo(&info.distro_id).await;
-    let colored_logo = process_logo_colors(&logo, &config);
-    display_output(colored_logo, &info, &config);
+    if cli.format == Format::Json {
+        match serde_json::to_string_pretty(&info) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                eprintln!("zfetch: couldn't serialize system info: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    let logo = match load_image(&config) {
+        // blank space the size of the picture, display_output draws it in afterwards
+        Some(PictureLogo::Graphics(image)) => Logo {
+            text: image.placeholder(),
+            small: None,
+            image: Some(image),
+        },
+        Some(PictureLogo::Text(text)) => Logo {
+            text,
+            small: None,
+            image: None,
+        },
+        None => load_logo(&config, &info).await,
+    };
+    display_output(&logo, &info, &config);
     // let elapsed = start.elapsed();
     // println!("\nExecution time: {:.2?}", elapsed); //uncomment everything for debugging speeds
 }
 
+// logo_type = "image": falls back to the text logos (with a note why) when it can't be drawn
+fn load_image(config: &Config) -> Option<PictureLogo> {
+    if config.logo_type != Some(LogoType::Image) {
+        return None;
+    }
+    let path = config::expand_home(config.logo_path.as_ref()?);
+    match load_image_logo(&path, config) {
+        Ok(image) => Some(image),
+        Err(err) => {
+            eprintln!("zfetch: {}", err);
+            None
+        }
+    }
+}
+
+// logo_path wins, then --logo / `logo`, then whatever matches the detected distro.
+// comes back colored and ready to print
+async fn load_logo(config: &Config, info: &SystemInfo) -> Logo {
+    if let Some(path) = config.logo_path.as_ref().filter(|_| config.logo_type != Some(LogoType::Image)) {
+        let path = config::expand_home(path);
+        match std::fs::read_to_string(&path) {
+            Ok(logo) => {
+                return Logo {
+                    text: process_logo_colors(&logo, None, config),
+                    small: None,
+                    image: None,
+                };
+            }
+            Err(err) => eprintln!("zfetch: couldn't read logo {}: {}", path.display(), err),
+        }
+    }
+    let base_id = match &config.logo {
+        Some(logo) => logo.clone(),
+        None => resolve_logo(&info.distro_id, &info.distro_like),
+    };
+    let logo_id = logo_variant(&base_id, config.logo_variant.unwrap_or(LogoVariant::Default));
+    // kept around in case the terminal is too narrow for the real one
+    let small_id = logo_variant(&base_id, LogoVariant::Small);
+    let small = if small_id != logo_id {
+        Some(process_logo_colors(&get_ascii_logo(&small_id).await, Some(&small_id), config))
+    } else {
+        None
+    };
+    Logo {
+        text: process_logo_colors(&get_ascii_logo(&logo_id).await, Some(&logo_id), config),
+        small,
+        image: None,
+    }
+}
+
+// --gen-config: drop a documented copy of the defaults where zfetch will pick it up
+fn gen_config(path: Option<&Path>, force: bool) -> i32 {
+    let Some(path) = path
+        .map(Path::to_path_buf)
+        .or_else(|| config::user_config_dir().map(|dir| dir.join("config.toml")))
+    else {
+        eprintln!("zfetch: couldn't work out where your config directory is, pass a path to --gen-config");
+        return 1;
+    };
+    match config::write_default_config(&path, force) {
+        Ok(()) => {
+            println!("wrote {}", path.display());
+            0
+        }
+        Err(err) => {
+            eprintln!("zfetch: {}", err);
+            1
+        }
+    }
+}
+
+// --check-config: list what was loaded and everything wrong with it, so dotfiles can be linted in CI
+fn check_config(report: &ConfigReport) -> i32 {
+    if report.files.is_empty() {
+        println!("no config files found, using the built-in defaults");
+    }
+    for file in &report.files {
+        println!("checked {}", file.display());
+    }
+    for err in &report.errors {
+        eprintln!("error: {}", err);
+    }
+    for warning in &report.warnings {
+        eprintln!("{}", warning);
+    }
+    if report.is_clean() { 0 } else { 1 }
+}
+
 // hi from the future
\ No newline at end of file
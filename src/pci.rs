@@ -0,0 +1,229 @@
+use std::io::Read;
+use std::path::Path;
+
+// where distros keep the pci.ids database (hwdata on Fedora/Arch, misc on Debian/Ubuntu)
+const PCI_IDS_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+    "/var/lib/pciutils/pci.ids",
+    "/usr/share/hwdata/pci.ids.gz",
+    "/usr/share/misc/pci.ids.gz",
+    "/usr/share/pci.ids.gz",
+];
+
+// for machines without pci.ids at all (containers, minimal installs) we can still
+// name the vendor of anything that's likely to be a display adapter
+const KNOWN_GPU_VENDORS: &[(u16, &str)] = &[
+    (0x1002, "AMD"),
+    (0x10de, "NVIDIA"),
+    (0x8086, "Intel"),
+    (0x102b, "Matrox"),
+    (0x1414, "Microsoft"),
+    (0x15ad, "VMware"),
+    (0x1a03, "ASPEED"),
+    (0x1af4, "Red Hat"),
+    (0x1234, "QEMU"),
+    (0x1b36, "Red Hat"),
+    (0x5143, "Qualcomm"),
+    (0x80ee, "VirtualBox"),
+];
+
+/// The pci.ids database, kept as text and searched on demand. There are only ever a
+/// couple of GPUs to look up so building an index would cost more than it saves.
+pub struct PciIds {
+    text: String,
+}
+
+#[derive(Default)]
+pub struct PciName {
+    pub vendor: Option<String>,
+    pub device: Option<String>,
+    pub subsystem_vendor: Option<String>,
+}
+
+impl PciIds {
+    pub fn load() -> Option<Self> {
+        PCI_IDS_PATHS.iter().find_map(|path| read_ids(Path::new(path))).map(|text| Self { text })
+    }
+
+    pub fn lookup(&self, vendor: u16, device: u16, subsystem_vendor: Option<u16>) -> PciName {
+        let mut name = PciName::default();
+        let vendor_key = format!("{:04x}", vendor);
+        let device_key = format!("{:04x}", device);
+
+        let mut in_vendor = false;
+        for line in self.text.lines() {
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            // the device classes come after all the vendors, nothing left for us there
+            if line.starts_with("C ") {
+                break;
+            }
+            if let Some(rest) = line.strip_prefix('\t') {
+                if in_vendor
+                    && !rest.starts_with('\t')
+                    && let Some((id, device_name)) = split_entry(rest)
+                    && id == device_key
+                {
+                    name.device = Some(device_name.to_string());
+                }
+                continue;
+            }
+            in_vendor = false;
+            if let Some((id, vendor_name)) = split_entry(line) {
+                if id == vendor_key {
+                    in_vendor = true;
+                    name.vendor = Some(vendor_name.to_string());
+                }
+                if Some(id) == subsystem_vendor.map(|v| format!("{:04x}", v)).as_deref() {
+                    name.subsystem_vendor = Some(vendor_name.to_string());
+                }
+            }
+        }
+        name
+    }
+}
+
+fn read_ids(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(bytes.as_slice()).read_to_string(&mut text).ok()?;
+        Some(text)
+    } else {
+        String::from_utf8(bytes).ok()
+    }
+}
+
+// "10de  NVIDIA Corporation" -> ("10de", "NVIDIA Corporation")
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let (id, name) = line.split_once("  ")?;
+    Some((id.trim(), name.trim()))
+}
+
+/// What we could work out about a PCI device from sysfs and pci.ids.
+pub struct PciDevice {
+    pub vendor_id: u16,
+    /// short vendor name, e.g. "NVIDIA" or "AMD"
+    pub vendor: String,
+    /// readable name like "NVIDIA GeForce RTX 3070 (ASUSTeK)"
+    pub name: String,
+}
+
+/// Turns a PCI device directory in sysfs (e.g. `/sys/class/drm/card0/device`) into a
+/// readable name. Falls back to the raw ids for whatever the database doesn't know.
+pub fn describe_device(device_dir: &Path, ids: Option<&PciIds>) -> Option<PciDevice> {
+    let vendor = read_hex(&device_dir.join("vendor"))?;
+    let device = read_hex(&device_dir.join("device"))?;
+    let subsystem_vendor = read_hex(&device_dir.join("subsystem_vendor")).filter(|&v| v != 0);
+
+    let name = ids.map(|ids| ids.lookup(vendor, device, subsystem_vendor)).unwrap_or_default();
+    let vendor_name = short_vendor(vendor, name.vendor.as_deref());
+    let device_name = name
+        .device
+        .as_deref()
+        .map(marketing_name)
+        .unwrap_or_else(|| format!("0x{:04x}", device));
+
+    let mut description = format!("{} {}", vendor_name, device_name);
+    // boards from ASUS, MSI, Sapphire and co carry their own vendor id on the subsystem
+    if let Some(board_vendor) = subsystem_vendor.filter(|&v| v != vendor) {
+        let board = short_vendor(board_vendor, name.subsystem_vendor.as_deref());
+        if !board.starts_with("0x") {
+            description.push_str(&format!(" ({})", board));
+        }
+    }
+    Some(PciDevice {
+        vendor_id: vendor,
+        vendor: vendor_name,
+        name: description,
+    })
+}
+
+/// Short name for the GPU vendors we know without pci.ids (Vulkan hands out PCI vendor ids too).
+pub fn known_vendor(id: u16) -> Option<&'static str> {
+    KNOWN_GPU_VENDORS.iter().find(|(known, _)| *known == id).map(|(_, short)| *short)
+}
+
+fn read_hex(path: &Path) -> Option<u16> {
+    let text = std::fs::read_to_string(path).ok()?;
+    u16::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+}
+
+// "Advanced Micro Devices, Inc. [AMD/ATI]" -> "AMD", "ASUSTeK Computer Inc." -> "ASUSTeK"
+fn short_vendor(id: u16, full_name: Option<&str>) -> String {
+    if let Some(short) = known_vendor(id) {
+        return short.to_string();
+    }
+    let Some(full_name) = full_name else {
+        return format!("0x{:04x}", id);
+    };
+    let mut name = full_name;
+    for suffix in [" Corporation", " Corp.", " Computer Inc.", " Co., Ltd.", " Co., Ltd", ", Inc.", " Inc.", " Ltd.", " GmbH"] {
+        if let Some(stripped) = name.strip_suffix(suffix) {
+            name = stripped;
+            break;
+        }
+    }
+    name.to_string()
+}
+
+// pci.ids names are "codename [Marketing Name]", people only know the bit in brackets
+// "GA104 [GeForce RTX 3070]" -> "GeForce RTX 3070"
+fn marketing_name(device: &str) -> String {
+    match (device.rfind('['), device.ends_with(']')) {
+        (Some(start), true) => device[start + 1..device.len() - 1].to_string(),
+        _ => device.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDS: &str = "\
+# comment line
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t73bf  Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]
+\t\t1043 04f2  TUF Gaming RX 6800 XT
+1043  ASUSTeK Computer Inc.
+10de  NVIDIA Corporation
+\t2484  GA104 [GeForce RTX 3070]
+\t\t10de 73bf  Not a device line
+C 03  Display controller
+\t00  VGA compatible controller
+";
+
+    fn ids() -> PciIds {
+        PciIds { text: IDS.to_string() }
+    }
+
+    #[test]
+    fn lookup_vendor_device_and_subsystem() {
+        let name = ids().lookup(0x1002, 0x73bf, Some(0x1043));
+        assert_eq!(name.vendor.as_deref(), Some("Advanced Micro Devices, Inc. [AMD/ATI]"));
+        assert_eq!(name.device.as_deref(), Some("Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]"));
+        assert_eq!(name.subsystem_vendor.as_deref(), Some("ASUSTeK Computer Inc."));
+    }
+
+    #[test]
+    fn lookup_skips_subsystem_lines_and_classes() {
+        // "\t\t10de 73bf" under NVIDIA is a subsystem entry, not device 0x73bf
+        let name = ids().lookup(0x10de, 0x73bf, None);
+        assert_eq!(name.vendor.as_deref(), Some("NVIDIA Corporation"));
+        assert_eq!(name.device, None);
+        // "\t00  VGA compatible controller" belongs to class 03, not to NVIDIA above it
+        let name = ids().lookup(0x10de, 0x0000, None);
+        assert_eq!(name.vendor.as_deref(), Some("NVIDIA Corporation"));
+        assert_eq!(name.device, None);
+    }
+
+    #[test]
+    fn marketing_names() {
+        assert_eq!(marketing_name("GA104 [GeForce RTX 3070]"), "GeForce RTX 3070");
+        assert_eq!(marketing_name("Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]"), "Radeon RX 6800/6800 XT / 6900 XT");
+        assert_eq!(marketing_name("Virtio GPU"), "Virtio GPU");
+    }
+}
@@ -1,62 +1,169 @@
-use crate::config::Config;
-use os_info;
-use battery;
+use crate::config::{Config, ModuleKind, Size, StorageConfig};
+use crate::pci::{self, PciIds};
 use tokio::process::Command;
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
+use serde::Serialize;
 
+// everything here is raw data (bytes, durations, percentages), turning it into
+// "11.91 GB" and friends is the UI's job
+#[derive(Serialize)]
 pub struct StorageInfo {
     pub name: String,
-    pub total: String,
-    pub used: String,
-    pub percent: u8, 
+    pub device: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub percent: f32,
     pub fs_type: String,
     pub readonly: bool,
+    pub removable: bool,
 }
 
+impl StorageInfo {
+    fn new(name: String, device: String, total_bytes: u64, used_bytes: u64, fs_type: String, readonly: bool) -> Self {
+        Self {
+            name,
+            device,
+            total_bytes,
+            used_bytes,
+            percent: percent_of(used_bytes, total_bytes),
+            fs_type,
+            readonly,
+            removable: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CpuInfo {
+    pub brand: String,
+    pub cores: usize,
+    pub frequency_mhz: u64,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuKind {
+    Integrated,
+    Discrete,
+    Virtual,
+    Unknown,
+}
+
+#[derive(Serialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: Option<String>,
+    /// kernel driver bound to the card, e.g. "amdgpu" or "nvidia"
+    pub driver: Option<String>,
+    pub vram_bytes: Option<u64>,
+    pub kind: GpuKind,
+    /// only filled in when `show_gpu_stats` is on
+    pub stats: Option<GpuStats>,
+}
+
+/// Live numbers for one card, whatever its driver exposes in sysfs.
+#[derive(Serialize, Default)]
+pub struct GpuStats {
+    pub temperature_celsius: Option<f32>,
+    pub busy_percent: Option<u8>,
+    pub clock_mhz: Option<u32>,
+}
+
+impl GpuInfo {
+    // for the paths that only ever get a name back (lspci, GL, Windows, ...)
+    fn named(name: String) -> Self {
+        Self {
+            name,
+            vendor: None,
+            driver: None,
+            vram_bytes: None,
+            kind: GpuKind::Unknown,
+            stats: None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+    Full,
+    Empty,
+    Unknown,
+}
+
+#[derive(Serialize)]
+pub struct BatteryInfo {
+    pub percent: f32,
+    pub state: BatteryState,
+}
+
+#[derive(Serialize)]
 pub struct SystemInfo {
     pub distro: String,
     pub distro_id: String,
+    /// ids of the distros this one is based on (os-release's ID_LIKE), closest first
+    pub distro_like: Vec<String>,
     pub kernel: String,
-    pub cpu: Option<String>,
-    pub gpu: Option<String>,
-    pub total_memory: Option<String>,
-    pub used_memory: Option<String>,
-    pub total_swap: Option<String>,
-    pub used_swap: Option<String>,
-    pub uptime: Option<String>,
+    pub cpu: Option<CpuInfo>,
+    pub gpus: Vec<GpuInfo>,
+    pub total_memory_bytes: Option<u64>,
+    pub used_memory_bytes: Option<u64>,
+    pub total_swap_bytes: Option<u64>,
+    pub used_swap_bytes: Option<u64>,
+    #[serde(rename = "uptime_secs", serialize_with = "serialize_secs")]
+    pub uptime: Option<Duration>,
     pub local_ip: Option<String>,
-    pub battery: Option<String>,
+    pub battery: Option<BatteryInfo>,
     pub storage: Vec<StorageInfo>,
     pub username: Option<String>,
     pub hostname: Option<String>,
 }
 
+fn serialize_secs<S: serde::Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    value.map(|d| d.as_secs()).serialize(serializer)
+}
+
+pub fn percent_of(used: u64, total: u64) -> f32 {
+    if total > 0 {
+        (used as f64 / total as f64 * 100.0) as f32
+    } else {
+        0.0
+    }
+}
+
 pub async fn get_system_info(config: &Config) -> SystemInfo {
-    let os_task = tokio::task::spawn_blocking(|| os_info::get());
+    let os_task = tokio::task::spawn_blocking(os_info::get);
     let sys_task = tokio::task::spawn_blocking(|| {
         let mut sys = sysinfo::System::new_all();
         sys.refresh_all();
         sys
     });
     let kernel_task = tokio::task::spawn_blocking(|| sysinfo::System::kernel_version().unwrap_or_default());
-    let uptime_task = if config.show_uptime.unwrap_or(true) {
-        Some(tokio::task::spawn_blocking(|| sysinfo::System::uptime()))
+    let uptime_task = if config.shows(ModuleKind::Uptime) {
+        Some(tokio::task::spawn_blocking(sysinfo::System::uptime))
     } else {
         None
     };
 
-    let gpu_task: Option<Pin<Box<dyn Future<Output = String> + Send>>> = if config.show_gpu.unwrap_or(true) {
+    let gpu_task: Option<Pin<Box<dyn Future<Output = Vec<GpuInfo>> + Send>>> = if config.shows(ModuleKind::Gpu) {
         Some(
             if cfg!(target_os = "macos") {
                 Box::pin(async {
+                    // IOKit only exists (and only links) on macOS
+                    #[cfg(target_os = "macos")]
                     let gpus = detect_gpu_iokit();
+                    #[cfg(not(target_os = "macos"))]
+                    let gpus: Vec<String> = Vec::new();
                     if !gpus.is_empty() {
-                        gpus.join(", ")
+                        gpus.into_iter().map(apple_gpu).collect()
                     } else {
                         // fallback to ioreg/system_profiler if needed
                         if let Ok(output) = Command::new("ioreg")
-                            .args(&["-r", "-c", "IOPCIDevice"])
+                            .args(["-r", "-c", "IOPCIDevice"])
                             .output()
                             .await
                         {
@@ -76,18 +183,21 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
                                     }
                                 })
                             {
-                                return model;
+                                return vec![apple_gpu(model)];
                             }
                         }
                         if let Ok(output) = Command::new("system_profiler")
-                            .args(&["SPDisplaysDataType", "-json"])
+                            .args(["SPDisplaysDataType", "-json"])
                             .output()
                             .await
                         {
                             let stdout = String::from_utf8_lossy(&output.stdout);
-                            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout) {
-                                if let Some(gpus) = json.get("SPDisplaysDataType").and_then(|v| v.as_array()) {
-                                    if let Some(gpu) = gpus.get(0) {
+                            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout)
+                                && let Some(gpus) = json.get("SPDisplaysDataType").and_then(|v| v.as_array())
+                            {
+                                return gpus
+                                    .iter()
+                                    .map(|gpu| {
                                         let model = gpu.get("sppci_model").and_then(|v| v.as_str()).unwrap_or("Unknown");
                                         let cores = gpu.get("spdisplays_gpu_core_count").and_then(|v| v.as_u64());
                                         let freq = gpu.get("spdisplays_gpu_core_clock").and_then(|v| v.as_str());
@@ -100,14 +210,12 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
                                                 details.push(')');
                                             }
                                         }
-                                        return details;
-                                    }
-                                }
+                                        apple_gpu(details)
+                                    })
+                                    .collect();
                             }
-                            "Unknown".to_string()
-                        } else {
-                            "Unknown".to_string()
                         }
+                        Vec::new()
                     }
                 })
             } else if cfg!(target_os = "windows") {
@@ -118,61 +226,45 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
                     let gpu_result = timeout(Duration::from_secs(5), async {
                         #[cfg(target_os = "windows")]
                         {
-                            let gpus = detect_gpu_windows();
-                            if !gpus.is_empty() {
-                                if gpus.len() == 1 {
-                                    format!("GPU: {}", gpus[0])
-                                } else {
-                                    gpus.iter()
-                                        .enumerate()
-                                        .map(|(i, name)| {
-                                            if i == 0 {
-                                                format!("GPU: {}", name)
-                                            } else {
-                                                format!("GPU {}: {}", i + 1, name)
-                                            }
-                                        })
-                                        .collect::<Vec<_>>()
-                                        .join("\n")
-                                }
-                            } else {
-                                "GPU: Unknown".to_string()
-                            }
+                            detect_gpu_windows().into_iter().map(GpuInfo::named).collect()
                         }
                         #[cfg(not(target_os = "windows"))]
                         {
-                            "GPU: Unknown".to_string()
+                            Vec::new()
                         }
                     }).await;
 
-                    match gpu_result {
-                        Ok(gpu_string) => gpu_string,
-                        _ => "GPU: Unknown".to_string(),
-                    }
+                    gpu_result.unwrap_or_default()
                 })
                 // yo i havent tried linux yet, but just report to me
             } else if cfg!(target_os = "linux") {
-                Box::pin(async {
+                let show_stats = config.show_gpu_stats.unwrap_or(false);
+                Box::pin(async move {
+                    let pci_ids = PciIds::load();
                     if let Ok(entries) = std::fs::read_dir("/sys/class/drm") {
-                        let mut gpus = Vec::new();
-                        for entry in entries.flatten() {
-                            let path = entry.path();
-                            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                                if name.starts_with("card") && !name.contains("-") {
-                                    let device_path = path.join("device");
-                                    let vendor_path = device_path.join("vendor");
-                                    let device_name_path = device_path.join("device");
-                                    if let (Ok(vendor), Ok(device)) = (
-                                        std::fs::read_to_string(&vendor_path),
-                                        std::fs::read_to_string(&device_name_path),
-                                    ) {
-                                        gpus.push(format!("PCI {}:{}", vendor.trim(), device.trim()));
-                                    }
+                        let mut cards: Vec<_> = entries
+                            .flatten()
+                            .map(|entry| entry.path())
+                            .filter(|path| {
+                                path.file_name()
+                                    .and_then(|n| n.to_str())
+                                    .is_some_and(|name| name.starts_with("card") && !name.contains('-'))
+                            })
+                            .collect();
+                        cards.sort();
+                        let gpus: Vec<GpuInfo> = cards
+                            .iter()
+                            .filter_map(|card| {
+                                let mut gpu = sysfs_gpu(&card.join("device"), pci_ids.as_ref())?;
+                                // only touch hwmon and friends when asked, some drivers wake the card up for it
+                                if show_stats {
+                                    gpu.stats = gpu_stats(card);
                                 }
-                            }
-                        }
+                                Some(gpu)
+                            })
+                            .collect();
                         if !gpus.is_empty() {
-                            return gpus.join(", ");
+                            return gpus;
                         }
                     }
                     if let Ok(output) = Command::new("lspci")
@@ -183,55 +275,45 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
                         let gpus: Vec<_> = stdout
                             .lines()
                             .filter(|line| line.contains(" VGA ") || line.contains("3D controller"))
-                            .map(|line| line.split(':').last().unwrap_or("").trim().to_string())
+                            .map(|line| GpuInfo::named(line.split(':').next_back().unwrap_or("").trim().to_string()))
                             .collect();
                         if !gpus.is_empty() {
-                            return gpus.join(", ");
+                            return gpus;
                         }
                     }
                     if let Ok(entries) = std::fs::read_dir("/sys/bus/pci/devices") {
                         let mut gpus = Vec::new();
                         for entry in entries.flatten() {
                             let path = entry.path();
-                            let class_path = path.join("class");
-                            if let Ok(class) = std::fs::read_to_string(&class_path) {
-                                if class.trim().starts_with("0x03") {
-                                    let vendor_path = path.join("vendor");
-                                    let device_path = path.join("device");
-                                    if let (Ok(vendor), Ok(device)) = (
-                                        std::fs::read_to_string(&vendor_path),
-                                        std::fs::read_to_string(&device_path),
-                                    ) {
-                                        gpus.push(format!("PCI {}:{}", vendor.trim(), device.trim()));
-                                    }
-                                }
+                            // 0x03xxxx is the display controller class
+                            if let Ok(class) = std::fs::read_to_string(path.join("class"))
+                                && class.trim().starts_with("0x03")
+                                && let Some(gpu) = sysfs_gpu(&path, pci_ids.as_ref())
+                            {
+                                gpus.push(gpu);
                             }
                         }
                         if !gpus.is_empty() {
-                            return gpus.join(", ");
+                            return gpus;
                         }
                     }
-                    if let Some(gl_gpu) = detect_gpu_opengl() {
-                        return gl_gpu;
-                    }
-                    if let Some(vk_gpu) = detect_gpu_vulkan() {
-                        return vk_gpu;
+                    let gl_gpus = detect_gpu_opengl();
+                    if !gl_gpus.is_empty() {
+                        return gl_gpus.into_iter().map(GpuInfo::named).collect();
                     }
-                    "Unknown".to_string()
+                    detect_gpu_vulkan()
                 })
             } else {
                 Box::pin(async {
                     tokio::task::spawn_blocking(|| {
-                        if let Some(gl_gpu) = detect_gpu_opengl() {
-                            return gl_gpu;
-                        }
-                        if let Some(vk_gpu) = detect_gpu_vulkan() {
-                            return vk_gpu;
+                        let gl_gpus = detect_gpu_opengl();
+                        if !gl_gpus.is_empty() {
+                            return gl_gpus.into_iter().map(GpuInfo::named).collect();
                         }
-                        "Unknown".to_string()
+                        detect_gpu_vulkan()
                     })
                     .await
-                    .unwrap_or_else(|_| "Unknown".to_string())
+                    .unwrap_or_default()
                 })
             }
         )
@@ -291,7 +373,11 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
         _ => format!("{} ({})", raw_os_type, os.version()),
     };
 
-    let distro_id = if raw_os_type.to_lowercase().contains("windows") {
+    let os_release = read_os_release();
+    let distro_like = os_release.as_ref().map(|r| r.id_like.clone()).unwrap_or_default();
+    let distro_id = if let Some(id) = os_release.and_then(|r| r.id) {
+        id
+    } else if raw_os_type.to_lowercase().contains("windows") {
         if version.starts_with("10.0.22") || version.contains("Windows 11") {
             "windows_11".to_string()
         } else {
@@ -303,65 +389,57 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
         raw_os_type.to_lowercase().replace(' ', "").chars().take(16).collect()
     };
 
-    let cpu = if config.show_cpu.unwrap_or(true) {
-        let cpu_brand = sys.cpus().get(0).map_or("Unknown".to_string(), |c| c.brand().to_string());
-        let cpu_cores = sys.physical_core_count().unwrap_or(sys.cpus().len());
-        let cpu_freq = sys.cpus().get(0).map_or(0, |c| c.frequency());
-        Some(format!(
-            "{} ({} cores) ({:.2} GHz)",
-            cpu_brand,
-            cpu_cores,
-            cpu_freq as f64 / 1000.0
-        ))
+    let cpu = if config.shows(ModuleKind::Cpu) {
+        Some(CpuInfo {
+            brand: sys.cpus().first().map_or("Unknown".to_string(), |c| c.brand().to_string()),
+            cores: sys.physical_core_count().unwrap_or(sys.cpus().len()),
+            frequency_mhz: sys.cpus().first().map_or(0, |c| c.frequency()),
+        })
     } else {
         None
     };
 
-    let (total_memory, used_memory, total_swap, used_swap) = if config.show_memory.unwrap_or(true) {
+    let (total_memory_bytes, used_memory_bytes, total_swap_bytes, used_swap_bytes) = if config.shows(ModuleKind::Memory) || config.shows(ModuleKind::Swap) {
         (
-            Some(format_bytes(sys.total_memory() / 1024)), // Convert the memory from KiB to MiB
-            Some(format_bytes(sys.used_memory() / 1024)),
-            Some(format_bytes(sys.total_swap() / 1024)),
-            Some(format_bytes(sys.used_swap() / 1024)),
+            Some(sys.total_memory()),
+            Some(sys.used_memory()),
+            Some(sys.total_swap()),
+            Some(sys.used_swap()),
         )
     } else {
         (None, None, None, None)
     };
 
-    let uptime = if config.show_uptime.unwrap_or(true) {
-        if let Some(uptime_secs) = uptime_secs {
-            let uptime_secs = uptime_secs.unwrap();
-            let days = uptime_secs / 86400;
-            let hours = (uptime_secs % 86400) / 3600;
-            let minutes = (uptime_secs % 3600) / 60;
-            Some(format!("{}d {}h {}m", days, hours, minutes))
-        } else {
-            None
-        }
+    let uptime = if config.shows(ModuleKind::Uptime) {
+        uptime_secs.and_then(|secs| secs.ok()).map(Duration::from_secs)
     } else {
         None
     };
 
-    let gpu = if config.show_gpu.unwrap_or(true) {
-        gpu.map(|g| g)
+    let gpus = if config.shows(ModuleKind::Gpu) {
+        gpu.unwrap_or_default()
     } else {
-        None
+        Vec::new()
     };
 
-    let local_ip = if config.show_local_ip.unwrap_or(true) {
+    let local_ip = if config.shows(ModuleKind::LocalIp) {
         get_local_ip()
     } else {
         None
     };
 
-    let battery = if config.show_battery.unwrap_or(true) {
+    let battery = if config.shows(ModuleKind::Battery) {
         get_battery_status()
     } else {
         None
     };
 
-    let storage = if config.show_storage.unwrap_or(true) {
-        get_storage_info().await
+    let storage = if config.shows(ModuleKind::Storage) {
+        let storage = get_storage_info().await;
+        match &config.storage {
+            Some(filter) => filter_storage(storage, filter),
+            None => storage,
+        }
     } else {
         Vec::new()
     };
@@ -372,13 +450,14 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
     SystemInfo {
         distro,
         distro_id,
+        distro_like,
         kernel,
         cpu,
-        gpu,
-        total_memory,
-        used_memory,
-        total_swap,
-        used_swap,
+        gpus,
+        total_memory_bytes,
+        used_memory_bytes,
+        total_swap_bytes,
+        used_swap_bytes,
         uptime,
         local_ip,
         battery,
@@ -388,81 +467,255 @@ pub async fn get_system_info(config: &Config) -> SystemInfo {
     }
 }
 
-fn format_bytes(kb: u64) -> String {
-    let gb = kb as f64 / 1024.0 / 1024.0;
-    format!("{:.2} GB", gb)
+struct OsRelease {
+    id: Option<String>,
+    id_like: Vec<String>,
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn detect_gpu_opengl() -> Option<String> {
-    use glutin::prelude::*;
-    use glutin::config::ConfigTemplateBuilder;
-    use glutin::context::{ContextApi, ContextAttributesBuilder};
-    use glutin::display::GetGlDisplay;
-    use glutin_winit::{DisplayBuilder, GlWindow};
-    use winit::event_loop::EventLoop;
-    use winit::window::WindowBuilder;
-    use raw_window_handle::HasRawWindowHandle;
-    use std::ffi::CString;
-
-    let event_loop = EventLoop::new();
-    let wb = WindowBuilder::new();
-    let template = ConfigTemplateBuilder::new();
-    let display_builder = DisplayBuilder::new().with_window_builder(Some(wb));
-    let (window, gl_config) = display_builder
-        .build(&event_loop, template, |mut configs| configs.next().expect("No GL config found"))
+// os_info only knows the distros it has a Type for and its names don't line up with the
+// logo files ("Linux Mint" vs linuxmint), os-release's ID is what every distro agrees on
+fn read_os_release() -> Option<OsRelease> {
+    let text = std::fs::read_to_string("/etc/os-release")
+        .or_else(|_| std::fs::read_to_string("/usr/lib/os-release"))
         .ok()?;
+    let mut release = OsRelease {
+        id: None,
+        id_like: Vec::new(),
+    };
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        match key.trim() {
+            "ID" if !value.is_empty() => release.id = Some(value.to_lowercase()),
+            "ID_LIKE" => release.id_like = value.split_whitespace().map(str::to_lowercase).collect(),
+            _ => {}
+        }
+    }
+    Some(release)
+}
 
-    let raw_window_handle = window.as_ref().map(|w| w.raw_window_handle());
-    let gl_display = gl_config.display();
+// everything sysfs knows about one card: pci.ids name, bound driver and (amdgpu) VRAM
+fn sysfs_gpu(device_dir: &std::path::Path, pci_ids: Option<&PciIds>) -> Option<GpuInfo> {
+    let device = pci::describe_device(device_dir, pci_ids)?;
+    let driver = std::fs::read_link(device_dir.join("driver"))
+        .ok()
+        .and_then(|link| link.file_name().map(|name| name.to_string_lossy().into_owned()));
+    let vram_bytes = std::fs::read_to_string(device_dir.join("mem_info_vram_total"))
+        .ok()
+        .and_then(|text| text.trim().parse::<u64>().ok())
+        .filter(|&bytes| bytes > 0);
+    let kind = gpu_kind(device_dir, device.vendor_id, vram_bytes);
+    Some(GpuInfo {
+        name: device.name,
+        vendor: Some(device.vendor),
+        driver,
+        vram_bytes,
+        kind,
+        stats: None,
+    })
+}
+
+// there's no "integrated" flag in sysfs, so go by vendor and where the card sits on the bus
+fn gpu_kind(device_dir: &std::path::Path, vendor_id: u16, vram_bytes: Option<u64>) -> GpuKind {
+    match vendor_id {
+        // virtio, qxl/bochs, VMware, VirtualBox, Hyper-V
+        0x1af4 | 0x1b36 | 0x1234 | 0x15ad | 0x80ee | 0x1414 => GpuKind::Virtual,
+        0x10de => GpuKind::Discrete,
+        0x8086 | 0x1002 => {
+            // integrated GPUs hang straight off the root complex (Intel's is always 00:02.0),
+            // add-in cards sit behind a bridge: /sys/devices/pci0000:00/0000:00:01.0/0000:01:00.0
+            let on_root_bus = std::fs::canonicalize(device_dir).ok().is_some_and(|path| {
+                path.parent()
+                    .and_then(|parent| parent.file_name())
+                    .is_some_and(|name| name.to_string_lossy().starts_with("pci"))
+            });
+            // AMD APUs do sit behind an internal bridge, but their "VRAM" is just the
+            // small carve-out the firmware reserves
+            let apu_carve_out = vendor_id == 0x1002 && vram_bytes.is_some_and(|bytes| bytes <= 1 << 30);
+            if on_root_bus || apu_carve_out {
+                GpuKind::Integrated
+            } else {
+                GpuKind::Discrete
+            }
+        }
+        _ => GpuKind::Unknown,
+    }
+}
 
-    let context_attributes = ContextAttributesBuilder::new()
-        .with_context_api(ContextApi::OpenGl(Some(glutin::context::Version::new(3, 3))));
-    let not_current_gl_context = unsafe {
-        gl_display.create_context(&gl_config, &context_attributes.build(raw_window_handle)).ok()?
+// temperature from hwmon (amdgpu, nouveau, i915 on newer kernels), load from amdgpu's
+// gpu_busy_percent and the current shader clock from whichever file the driver has
+fn gpu_stats(card: &std::path::Path) -> Option<GpuStats> {
+    let device = card.join("device");
+    let read_number = |path: std::path::PathBuf| {
+        std::fs::read_to_string(path).ok().and_then(|text| text.trim().parse::<u64>().ok())
     };
-    let window = window?;
-    let attrs = window.build_surface_attributes(<_>::default());
-    let gl_surface = unsafe { gl_display.create_window_surface(&gl_config, &attrs).ok()? };
-    let _gl_context = not_current_gl_context.make_current(&gl_surface).ok()?;
-
-    gl::load_with(|symbol| {
-        let c_str = CString::new(symbol).unwrap();
-        gl_display.get_proc_address(&c_str) as *const _
-    });
 
-    let renderer = unsafe {
-        let ptr = gl::GetString(gl::RENDERER);
-        if ptr.is_null() {
-            return None;
-        }
-        std::ffi::CStr::from_ptr(ptr as *const i8)
-            .to_str()
-            .ok()
-            .map(|s| s.to_string())
+    let temperature_celsius = std::fs::read_dir(device.join("hwmon"))
+        .ok()
+        .and_then(|entries| entries.flatten().find_map(|hwmon| read_number(hwmon.path().join("temp1_input"))))
+        .map(|millidegrees| millidegrees as f32 / 1000.0);
+    let busy_percent = read_number(device.join("gpu_busy_percent")).map(|percent| percent.min(100) as u8);
+    // pp_dpm_sclk lists every amdgpu power state, the active one is marked: "1: 1850Mhz *"
+    let clock_mhz = std::fs::read_to_string(device.join("pp_dpm_sclk"))
+        .ok()
+        .and_then(|text| {
+            let active = text.lines().find(|line| line.trim_end().ends_with('*'))?;
+            let (_, clock) = active.split_once(':')?;
+            clock.trim().trim_end_matches('*').trim().trim_end_matches("Mhz").trim_end_matches("MHz").parse().ok()
+        })
+        .or_else(|| read_number(card.join("gt_act_freq_mhz")).map(|mhz| mhz as u32))
+        .or_else(|| read_number(device.join("tile0/gt0/freq0/act_freq")).map(|mhz| mhz as u32));
+
+    let stats = GpuStats {
+        temperature_celsius,
+        busy_percent,
+        clock_mhz,
+    };
+    (stats.temperature_celsius.is_some() || stats.busy_percent.is_some() || stats.clock_mhz.is_some()).then_some(stats)
+}
+
+// every GPU in a Mac made by Apple is part of the SoC
+fn apple_gpu(name: String) -> GpuInfo {
+    let mut gpu = GpuInfo::named(name);
+    if gpu.name.starts_with("Apple") {
+        gpu.vendor = Some("Apple".to_string());
+        gpu.kind = GpuKind::Integrated;
+    }
+    gpu
+}
+
+// headless GL: ask EGL for its devices (EGL_EXT_device_enumeration) and make a context
+// current without any surface, so this works over SSH, in containers and in CI with no
+// display server. the old version opened a real window just to read GL_RENDERER
+#[cfg(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn detect_gpu_opengl() -> Vec<String> {
+    use glutin::api::egl::device::Device;
+    use glutin::api::egl::display::Display;
+    use glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+    use glutin::context::{ContextApi, ContextAttributesBuilder};
+    use glutin::prelude::*;
+    use std::ffi::{CStr, CString};
+
+    let Ok(devices) = Device::query_devices() else {
+        return Vec::new();
     };
-    renderer
+    let devices: Vec<Device> = devices.collect();
+    let mut renderers = Vec::new();
+    for device in &devices {
+        let Ok(display) = (unsafe { Display::with_device(device, None) }) else {
+            continue;
+        };
+        let template = ConfigTemplateBuilder::new()
+            .with_surface_type(ConfigSurfaceTypes::empty())
+            .build();
+        let Some(gl_config) = (unsafe { display.find_configs(template) }).ok().and_then(|mut c| c.next()) else {
+            continue;
+        };
+        let attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::OpenGl(None))
+            .build(None);
+        let Ok(context) = (unsafe { display.create_context(&gl_config, &attributes) }) else {
+            continue;
+        };
+        let Ok(_current) = context.make_current_surfaceless() else {
+            continue;
+        };
+
+        gl::load_with(|symbol| {
+            CString::new(symbol).map_or(std::ptr::null(), |name| display.get_proc_address(&name))
+        });
+        let renderer = unsafe {
+            let ptr = gl::GetString(gl::RENDERER);
+            (!ptr.is_null()).then(|| CStr::from_ptr(ptr as *const _).to_string_lossy().into_owned())
+        };
+        // mesa's software fallback shows up as a "device" too, it's not a GPU
+        if let Some(renderer) = renderer.filter(|r| !r.starts_with("llvmpipe") && !r.starts_with("softpipe"))
+            && !renderers.contains(&renderer)
+        {
+            renderers.push(renderer);
+        }
+    }
+    renderers
 }
 
-fn detect_gpu_vulkan() -> Option<String> {
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn detect_gpu_opengl() -> Vec<String> {
+    Vec::new()
+}
+
+// vulkan never needed a window, but it used to blindly take physical_devices[0] (and
+// panic when there were none) and leak the instance
+fn detect_gpu_vulkan() -> Vec<GpuInfo> {
     use ash::vk;
     use ash::Entry;
 
-    let entry = unsafe { Entry::load().ok()? };
-    let app_name = std::ffi::CString::new("ZFetch").unwrap();
-    let engine_name = std::ffi::CString::new("No Engine").unwrap();
+    let Ok(entry) = (unsafe { Entry::load() }) else {
+        return Vec::new();
+    };
     let app_info = vk::ApplicationInfo::builder()
-        .application_name(app_name.as_c_str())
+        .application_name(c"ZFetch")
         .application_version(vk::make_api_version(0, 1, 0, 0))
-        .engine_name(engine_name.as_c_str())
+        .engine_name(c"No Engine")
         .engine_version(vk::make_api_version(0, 1, 0, 0))
         .api_version(vk::API_VERSION_1_0);
     let create_info = vk::InstanceCreateInfo::builder().application_info(&app_info);
-    let instance = unsafe { entry.create_instance(&create_info, None).ok()? };
-    let physical_devices = unsafe { instance.enumerate_physical_devices().ok()? };
-    let props = unsafe { instance.get_physical_device_properties(physical_devices[0]) };
-    let name = unsafe { std::ffi::CStr::from_ptr(props.device_name.as_ptr()) };
-    Some(name.to_string_lossy().to_string())
+    let Ok(instance) = (unsafe { entry.create_instance(&create_info, None) }) else {
+        return Vec::new();
+    };
+
+    let physical_devices = unsafe { instance.enumerate_physical_devices() }.unwrap_or_default();
+    let gpus = physical_devices
+        .iter()
+        .map(|&device| unsafe {
+            (
+                instance.get_physical_device_properties(device),
+                instance.get_physical_device_memory_properties(device),
+            )
+        })
+        .filter(|(props, _)| props.device_type != vk::PhysicalDeviceType::CPU)
+        .map(|(props, memory)| {
+            let kind = match props.device_type {
+                vk::PhysicalDeviceType::INTEGRATED_GPU => GpuKind::Integrated,
+                vk::PhysicalDeviceType::DISCRETE_GPU => GpuKind::Discrete,
+                vk::PhysicalDeviceType::VIRTUAL_GPU => GpuKind::Virtual,
+                _ => GpuKind::Unknown,
+            };
+            // an integrated GPU's device-local heap is just system RAM, only count real VRAM
+            let vram_bytes = (kind == GpuKind::Discrete)
+                .then(|| {
+                    memory.memory_heaps[..memory.memory_heap_count as usize]
+                        .iter()
+                        .filter(|heap| heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL))
+                        .map(|heap| heap.size)
+                        .max()
+                })
+                .flatten();
+            GpuInfo {
+                name: unsafe { std::ffi::CStr::from_ptr(props.device_name.as_ptr()) }.to_string_lossy().into_owned(),
+                vendor: u16::try_from(props.vendor_id).ok().and_then(pci::known_vendor).map(str::to_string),
+                driver: None,
+                vram_bytes,
+                kind,
+                stats: None,
+            }
+        })
+        .collect();
+    unsafe { instance.destroy_instance(None) };
+    gpus
 }
 
 // Dummy implementations for local_ip and battery
@@ -478,7 +731,7 @@ fn get_local_ip() -> Option<String> {
         .map(|addr| addr.ip().to_string())
 }
 
-fn get_battery_status() -> Option<String> {
+fn get_battery_status() -> Option<BatteryInfo> {
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
     {
         use battery::Manager;
@@ -487,22 +740,15 @@ fn get_battery_status() -> Option<String> {
         let battery = batteries.next()?.ok()?;
 
         let percent = battery.state_of_charge().get::<battery::units::ratio::percent>();
-        let _status = match battery.state() {
-            battery::State::Charging => "Charging",
-            battery::State::Full => "Full",
-            battery::State::Discharging => "Discharging",
-            battery::State::Empty => "Empty",
-            battery::State::Unknown => "Unknown",
-            _ => "Unknown",
-        };
-
-        let plugged = if matches!(battery.state(), battery::State::Charging | battery::State::Full) {
-            "[AC Connected]"
-        } else {
-            "[Discharging]"
+        let state = match battery.state() {
+            battery::State::Charging => BatteryState::Charging,
+            battery::State::Full => BatteryState::Full,
+            battery::State::Discharging => BatteryState::Discharging,
+            battery::State::Empty => BatteryState::Empty,
+            _ => BatteryState::Unknown,
         };
 
-        Some(format!("{}% {}", percent.round(), plugged))
+        Some(BatteryInfo { percent, state })
     }
     #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
     {
@@ -515,37 +761,7 @@ async fn get_storage_info() -> Vec<StorageInfo> {
 
     #[cfg(target_os = "linux")]
     {
-        use tokio::process::Command;
-        if let Ok(output) = Command::new("df")
-            .arg("-k")
-            .arg("/")
-            .output()
-            .await
-        {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines().skip(1) {
-                let columns: Vec<&str> = line.split_whitespace().collect();
-                if columns.len() >= 6 {
-                    let total_kb: u64 = columns[1].parse().unwrap_or(0);
-                    let avail_kb: u64 = columns[3].parse().unwrap_or(0);
-                    let used_kb = total_kb.saturating_sub(avail_kb);
-                    let percent = if total_kb > 0 {
-                        ((used_kb as f64 / total_kb as f64) * 100.0).round() as u8
-                    } else {
-                        0
-                    };
-
-                    storage_info.push(StorageInfo {
-                        name: "/".to_string(),
-                        total: format_bytes(total_kb),
-                        used: format_bytes(used_kb),
-                        percent,
-                        fs_type: "apfs".to_string(),
-                        readonly: false,
-                    });
-                }
-            }
-        }
+        storage_info.extend(get_linux_mounts());
     }
 
     #[cfg(target_os = "macos")]
@@ -564,16 +780,15 @@ async fn get_storage_info() -> Vec<StorageInfo> {
                     let total_kb: u64 = columns[1].parse().unwrap_or(0);
                     let avail_kb: u64 = columns[3].parse().unwrap_or(0);
                     let used_kb = total_kb.saturating_sub(avail_kb);
-                    let percent = columns[4].trim_end_matches('%').parse().unwrap_or(0);
-
-                    storage_info.push(StorageInfo {
-                        name: "/".to_string(),
-                        total: format_bytes(total_kb),
-                        used: format_bytes(used_kb),
-                        percent,
-                        fs_type: "apfs".to_string(),
-                        readonly: false,
-                    });
+
+                    storage_info.push(StorageInfo::new(
+                        "/".to_string(),
+                        columns[0].to_string(),
+                        total_kb * 1024,
+                        used_kb * 1024,
+                        "apfs".to_string(),
+                        false,
+                    ));
                 }
             }
         }
@@ -595,21 +810,9 @@ async fn get_storage_info() -> Vec<StorageInfo> {
                     let total: u64 = columns[1].parse().unwrap_or(0);
                     let free: u64 = columns[2].parse().unwrap_or(0);
                     let used = total.saturating_sub(free);
-                    let percent = if total > 0 {
-                        ((used as f64 / total as f64) * 100.0).round() as u8
-                    } else {
-                        0
-                    };
                     let fs_type = columns[3].to_string();
 
-                    storage_info.push(StorageInfo {
-                        name: "C:/".to_string(),
-                        total: format_bytes(total / 1024),
-                        used: format_bytes(used / 1024),
-                        percent,
-                        fs_type,
-                        readonly: false,
-                    });
+                    storage_info.push(StorageInfo::new("C:/".to_string(), columns[0].to_string(), total, used, fs_type, false));
                 }
             }
         }
@@ -618,6 +821,170 @@ async fn get_storage_info() -> Vec<StorageInfo> {
     storage_info
 }
 
+/// Drops the filesystems the `storage` config section doesn't want as rows.
+fn filter_storage(storage: Vec<StorageInfo>, filter: &StorageConfig) -> Vec<StorageInfo> {
+    let include = compile_globs(filter.include_mounts.as_deref());
+    let exclude = compile_globs(filter.exclude_mounts.as_deref()).unwrap_or_default();
+    let exclude_fs_types = filter.exclude_fs_types.as_deref().unwrap_or_default();
+    let min_size = filter.min_size.as_ref().and_then(Size::bytes).unwrap_or(0);
+    let show_removable = filter.show_removable.unwrap_or(true);
+
+    storage
+        .into_iter()
+        .filter(|disk| {
+            include.as_ref().is_none_or(|globs| globs.iter().any(|g| g.matches_with(&disk.name, MOUNT_GLOB)))
+                && !exclude.iter().any(|g| g.matches_with(&disk.name, MOUNT_GLOB))
+                && !exclude_fs_types.iter().any(|t| t.eq_ignore_ascii_case(&disk.fs_type))
+                && disk.total_bytes >= min_size
+                && (show_removable || !disk.removable)
+        })
+        .collect()
+}
+
+// shell-style: "/mnt/*" is what's directly in /mnt, "/mnt/**" is everything below it
+const MOUNT_GLOB: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+// bad patterns are reported by config validation, here they just don't match anything
+fn compile_globs(patterns: Option<&[String]>) -> Option<Vec<glob::Pattern>> {
+    patterns.map(|list| list.iter().filter_map(|p| glob::Pattern::new(p).ok()).collect())
+}
+
+// stuff that shows up in mountinfo but isn't storage anybody wants to see
+#[cfg(target_os = "linux")]
+const PSEUDO_FS_TYPES: &[&str] = &[
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs", "devpts",
+    "devtmpfs", "efivarfs", "fuse.gvfsd-fuse", "fuse.lxcfs", "fuse.portal", "fuse.snapfuse",
+    "fusectl", "hugetlbfs", "mqueue", "nfsd", "nsfs", "overlay", "proc", "pstore", "ramfs",
+    "rpc_pipefs", "securityfs", "selinuxfs", "squashfs", "sysfs", "tmpfs", "tracefs",
+];
+
+#[cfg(target_os = "linux")]
+struct MountEntry {
+    device_id: String,
+    mount_point: String,
+    fs_type: String,
+    source: String,
+    readonly: bool,
+}
+
+// every real filesystem from /proc/self/mountinfo, sized with statvfs. no more shelling out to df
+#[cfg(target_os = "linux")]
+fn get_linux_mounts() -> Vec<StorageInfo> {
+    let Ok(mountinfo) = std::fs::read_to_string("/proc/self/mountinfo") else {
+        return Vec::new();
+    };
+
+    let mut seen_devices: Vec<String> = Vec::new();
+    let mut mounts = Vec::new();
+    for entry in mountinfo.lines().filter_map(parse_mountinfo_line) {
+        if PSEUDO_FS_TYPES.contains(&entry.fs_type.as_str()) {
+            continue;
+        }
+        // bind mounts (and btrfs subvolumes) are the same device showing up again, the
+        // first one listed is the real mount
+        if seen_devices.contains(&entry.device_id) {
+            continue;
+        }
+        let Some((total, free)) = statvfs_bytes(&entry.mount_point) else {
+            continue;
+        };
+        if total == 0 {
+            continue;
+        }
+        seen_devices.push(entry.device_id);
+        let removable = is_removable(&entry.source);
+        let mut info = StorageInfo::new(
+            entry.mount_point,
+            entry.source,
+            total,
+            total.saturating_sub(free),
+            entry.fs_type,
+            entry.readonly,
+        );
+        info.removable = removable;
+        mounts.push(info);
+    }
+    mounts
+}
+
+// /dev/sdb1 -> /sys/class/block/sdb1. partitions don't carry their own `removable`
+// flag so check the whole disk too, and plenty of USB drives claim 0 so trust the bus
+#[cfg(target_os = "linux")]
+fn is_removable(source: &str) -> bool {
+    let Some(name) = source.strip_prefix("/dev/") else {
+        return false;
+    };
+    let Ok(device) = std::fs::canonicalize(std::path::Path::new("/sys/class/block").join(name)) else {
+        return false;
+    };
+    let flagged = ["removable", "../removable"]
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(device.join(file)).ok())
+        .any(|flag| flag.trim() == "1");
+    flagged || device.to_string_lossy().contains("/usb")
+}
+
+// 36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+// (the optional fields before "-" can be any length, including none)
+#[cfg(target_os = "linux")]
+fn parse_mountinfo_line(line: &str) -> Option<MountEntry> {
+    let (left, right) = line.split_once(" - ")?;
+    let left: Vec<&str> = left.split(' ').collect();
+    let right: Vec<&str> = right.split(' ').collect();
+    if left.len() < 6 || right.len() < 3 {
+        return None;
+    }
+    let mount_options = left[5];
+    let super_options = right[2];
+    let readonly = mount_options.split(',').any(|o| o == "ro") || super_options.split(',').any(|o| o == "ro");
+    Some(MountEntry {
+        device_id: left[2].to_string(),
+        mount_point: unescape_mount_path(left[4]),
+        fs_type: right[0].to_string(),
+        source: unescape_mount_path(right[1]),
+        readonly,
+    })
+}
+
+// the kernel writes spaces, tabs, newlines and backslashes in paths as \040-style octal
+#[cfg(target_os = "linux")]
+fn unescape_mount_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && i + 3 < bytes.len()
+            && let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 4])
+                .ok()
+                .and_then(|oct| u8::from_str_radix(oct, 8).ok())
+        {
+            out.push(byte);
+            i += 4;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// (total, free) in bytes
+#[cfg(target_os = "linux")]
+fn statvfs_bytes(path: &str) -> Option<(u64, u64)> {
+    let c_path = std::ffi::CString::new(path).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let block_size = stat.f_frsize as u64;
+    Some((stat.f_blocks as u64 * block_size, stat.f_bfree as u64 * block_size))
+}
+
 #[cfg(target_os = "windows")]
 fn detect_gpu_windows() -> Vec<String> {
     use windows::{
@@ -628,12 +995,14 @@ fn detect_gpu_windows() -> Vec<String> {
     let mut gpus = Vec::new();
 
     unsafe {
-        let hdev = SetupDiGetClassDevsW(
+        let Ok(hdev) = SetupDiGetClassDevsW(
             Some(&GUID_DEVCLASS_DISPLAY),
             None,
             None,
             DIGCF_PRESENT,
-        ).expect("SetupDiGetClassDevsW failed");
+        ) else {
+            return gpus;
+        };
 
         let mut index = 0;
         loop {
@@ -765,4 +1134,109 @@ fn detect_gpu_iokit() -> Vec<String> {
 // Ok so, holy shit first of all, using IOKit to send direct API calls to grab the gpu info
 // was so much better than using system profiler, i went from ~300ms delays to ~20ms after using IOKit.
 // And windows is so much better, the first version of this when i tried it took EIGHTEEN seconds
-// just for it to say N/A.
\ No newline at end of file
+// just for it to say N/A.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(name: &str, fs_type: &str, total_bytes: u64, removable: bool) -> StorageInfo {
+        StorageInfo {
+            removable,
+            ..StorageInfo::new(name.to_string(), String::new(), total_bytes, 0, fs_type.to_string(), false)
+        }
+    }
+
+    fn shown(filter: &StorageConfig) -> Vec<String> {
+        let disks = vec![
+            disk("/", "ext4", 500 << 30, false),
+            disk("/boot/efi", "vfat", 512 << 20, false),
+            disk("/mnt/data", "xfs", 2 << 40, false),
+            disk("/mnt/data/backup", "btrfs", 1 << 40, false),
+            disk("/run/media/me/STICK", "exfat", 32 << 30, true),
+        ];
+        filter_storage(disks, filter).into_iter().map(|d| d.name).collect()
+    }
+
+    #[test]
+    fn storage_include_and_exclude_mounts() {
+        let include = StorageConfig {
+            include_mounts: Some(vec!["/".to_string(), "/mnt/*".to_string()]),
+            ..Default::default()
+        };
+        // "*" doesn't reach into /mnt/data/backup
+        assert_eq!(shown(&include), ["/", "/mnt/data"]);
+
+        let exclude = StorageConfig {
+            exclude_mounts: Some(vec!["/mnt/**".to_string(), "/boot/*".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(shown(&exclude), ["/", "/run/media/me/STICK"]);
+
+        let everything_at_the_top = StorageConfig {
+            include_mounts: Some(vec!["/*".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(shown(&everything_at_the_top), ["/"]);
+    }
+
+    #[test]
+    fn storage_fs_type_size_and_removable() {
+        let fs_types = StorageConfig {
+            exclude_fs_types: Some(vec!["VFAT".to_string(), "btrfs".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(shown(&fs_types), ["/", "/mnt/data", "/run/media/me/STICK"]);
+
+        let min_size = StorageConfig {
+            min_size: Some(Size::Text("1 GiB".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(shown(&min_size), ["/", "/mnt/data", "/mnt/data/backup", "/run/media/me/STICK"]);
+
+        let no_removable = StorageConfig {
+            show_removable: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(shown(&no_removable), ["/", "/boot/efi", "/mnt/data", "/mnt/data/backup"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mountinfo_kernel_doc_example() {
+        // the example from Documentation/filesystems/proc.rst, one optional field
+        let entry = parse_mountinfo_line("36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw,errors=continue").unwrap();
+        assert_eq!(entry.device_id, "98:0");
+        assert_eq!(entry.mount_point, "/mnt/parent");
+        assert_eq!(entry.fs_type, "ext3");
+        assert_eq!(entry.source, "/dev/root");
+        assert!(!entry.readonly);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mountinfo_without_optional_fields() {
+        let entry = parse_mountinfo_line("29 1 259:2 / / ro,relatime - btrfs /dev/nvme0n1p2 rw,ssd,subvol=/").unwrap();
+        assert_eq!(entry.device_id, "259:2");
+        assert_eq!(entry.mount_point, "/");
+        assert_eq!(entry.fs_type, "btrfs");
+        assert_eq!(entry.source, "/dev/nvme0n1p2");
+        // "ro" in the per-mount options is enough
+        assert!(entry.readonly);
+        assert!(parse_mountinfo_line("29 1 259:2 / / rw").is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mountinfo_escaped_path() {
+        let entry = parse_mountinfo_line(
+            "112 29 8:17 / /run/media/me/My\\040Stick rw,nosuid shared:60 master:2 - vfat /dev/sdb1 ro,uid=1000",
+        )
+        .unwrap();
+        assert_eq!(entry.mount_point, "/run/media/me/My Stick");
+        assert!(entry.readonly);
+        assert_eq!(unescape_mount_path("tab\\011and\\134backslash"), "tab\tand\\backslash");
+        // not an escape, left as is
+        assert_eq!(unescape_mount_path("trailing\\04"), "trailing\\04");
+    }
+}
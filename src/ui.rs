@@ -1,122 +1,189 @@
-use crate::system::SystemInfo;
-use crate::config::Config;
+use crate::system::{percent_of, BatteryInfo, BatteryState, CpuInfo, GpuInfo, GpuKind, GpuStats, SystemInfo};
+use crate::config::{border_char, BoxPreset, BoxStyle, ByteUnits, Config, LogoPosition, ModuleKind, VerticalAlign, DEFAULT_GAP, MAX_PRECISION};
+use crate::graphics::ImageLogo;
 use crossterm::style::{Color, SetForegroundColor, ResetColor};
 use std::io::{self, Write};
 use regex::Regex;
+use std::sync::LazyLock;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-// strip the ANSI codes and measure visible width
-fn visible_width(s: &str) -> usize {
-    // match ANSI escape sequences
-    let ansi_re = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
-    ansi_re.replace_all(s, "").len()
+// color codes, the only escapes that end up in logo and box lines
+static ANSI_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
+/// How many terminal columns `s` takes up: color codes take none, CJK and emoji take two,
+/// and combining marks or ZWJ sequences count once with the character they're attached to.
+pub(crate) fn visible_width(s: &str) -> usize {
+    ANSI_RE
+        .replace_all(s, "")
+        .graphemes(true)
+        .map(|grapheme| grapheme.width().min(2))
+        .sum()
+}
+
+// format!("{:width$}") pads by chars, which is wrong for anything double width
+fn pad_to(s: &str, width: usize) -> String {
+    format!("{}{}", s, " ".repeat(width.saturating_sub(visible_width(s))))
+}
+
+/// The characters the info box is drawn with.
+struct Frame {
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+    horizontal: char,
+    vertical: char,
+    left_tee: char,
+    right_tee: char,
+}
+
+const fn frame(corners: [char; 4], horizontal: char, vertical: char, tees: [char; 2]) -> Frame {
+    Frame {
+        top_left: corners[0],
+        top_right: corners[1],
+        bottom_left: corners[2],
+        bottom_right: corners[3],
+        horizontal,
+        vertical,
+        left_tee: tees[0],
+        right_tee: tees[1],
+    }
+}
+
+const SINGLE: Frame = frame(['┌', '┐', '└', '┘'], '─', '│', ['├', '┤']);
+const ROUNDED: Frame = frame(['╭', '╮', '╰', '╯'], '─', '│', ['├', '┤']);
+const DOUBLE: Frame = frame(['╔', '╗', '╚', '╝'], '═', '║', ['╠', '╣']);
+const HEAVY: Frame = frame(['┏', '┓', '┗', '┛'], '━', '┃', ['┣', '┫']);
+const ASCII: Frame = frame(['+', '+', '+', '+'], '-', '|', ['+', '+']);
+// same shape as the others so everything still lines up, the rules get dropped
+const BORDERLESS: Frame = frame([' '; 4], ' ', ' ', [' '; 2]);
+
+impl Frame {
+    fn from_style(style: Option<&BoxStyle>) -> Frame {
+        let preset = match style {
+            Some(BoxStyle::Custom(chars)) => {
+                // validate() already warned about anything that isn't one narrow character,
+                // those get the "single" one so the box still lines up
+                let pick = |value: &Option<String>, fallback: char| value.as_deref().and_then(border_char).unwrap_or(fallback);
+                return Frame {
+                    top_left: pick(&chars.top_left, SINGLE.top_left),
+                    top_right: pick(&chars.top_right, SINGLE.top_right),
+                    bottom_left: pick(&chars.bottom_left, SINGLE.bottom_left),
+                    bottom_right: pick(&chars.bottom_right, SINGLE.bottom_right),
+                    horizontal: pick(&chars.horizontal, SINGLE.horizontal),
+                    vertical: pick(&chars.vertical, SINGLE.vertical),
+                    left_tee: pick(&chars.left_tee, SINGLE.left_tee),
+                    right_tee: pick(&chars.right_tee, SINGLE.right_tee),
+                };
+            }
+            Some(style) => style.preset().unwrap_or(BoxPreset::Single),
+            None => BoxPreset::Single,
+        };
+        match preset {
+            BoxPreset::Single => SINGLE,
+            BoxPreset::Rounded => ROUNDED,
+            BoxPreset::Double => DOUBLE,
+            BoxPreset::Heavy => HEAVY,
+            BoxPreset::Ascii => ASCII,
+            BoxPreset::None => BORDERLESS,
+        }
+    }
+
+    // ┌────┐, ├────┤ or └────┘ across the whole box
+    fn rule(&self, left: char, right: char, box_width: usize) -> String {
+        let middle = self.horizontal.to_string().repeat(box_width.saturating_sub(2));
+        format!("{}{}{}", left, middle, right)
+    }
 }
 
-fn pad_box_title(title: &str, box_width: usize) -> String {
+fn pad_box_title(title: &str, box_width: usize, frame: &Frame) -> String {
     let left_pad = "  ";
     let content_width = box_width.saturating_sub(2 + left_pad.len());
-    let centered = format!("{:^width$}", title, width = content_width);
-    let pad = content_width.saturating_sub(centered.len());
-    format!("│{}{}{}│", left_pad, centered, " ".repeat(pad))
+    let left = content_width.saturating_sub(visible_width(title)) / 2;
+    let centered = pad_to(&format!("{}{}", " ".repeat(left), title), content_width);
+    format!("{}{}{}{}", frame.vertical, left_pad, centered, frame.vertical)
 }
 
-fn pad_box_line(label: &str, value: &str, box_width: usize) -> String {
+fn box_content(label: &str, value: &str, label_width: usize) -> String {
+    format!("{}: {}", pad_to(label, label_width), value)
+}
+
+fn pad_box_line(label: &str, value: &str, label_width: usize, box_width: usize, frame: &Frame) -> String {
     let left_pad = "  ";
-    let label_width = 10;
-    let content = format!("{:label_width$}: {}", label, value, label_width = label_width);
     let content_width = box_width.saturating_sub(2 + left_pad.len());
-    let pad = content_width.saturating_sub(content.len());
-    format!("│{}{}{}│", left_pad, content, " ".repeat(pad))
+    format!(
+        "{}{}{}{}",
+        frame.vertical,
+        left_pad,
+        pad_to(&box_content(label, value, label_width), content_width),
+        frame.vertical
+    )
 }
 
-pub fn display_output(logo: String, info: &SystemInfo, config: &Config) {
-    let logo_lines: Vec<&str> = logo.lines().collect();
+/// Everything that can go in the logo column. `small` is the fallback for terminals too
+/// narrow for `text`, and an image gets drawn over the blank `text` reserved for it.
+pub struct Logo {
+    pub text: String,
+    pub small: Option<String>,
+    pub image: Option<ImageLogo>,
+}
 
+pub fn display_output(logo: &Logo, info: &SystemInfo, config: &Config) {
     // get info ready as label/value pairs
-    let used_mem = parse_gb(info.used_memory.as_deref().unwrap_or("0"));
-    let total_mem = parse_gb(info.total_memory.as_deref().unwrap_or("0"));
-    let mem_percent = if total_mem > 0.0 {
-        (used_mem / total_mem * 100.0).round()
-    } else {
-        0.0
-    };
-    let mem_val = format!(
-        "{} / {} ({:.0}%)",
-        info.used_memory.as_deref().unwrap_or("N/A"),
-        info.total_memory.as_deref().unwrap_or("N/A"),
-        mem_percent
-    );
-
-    let used_swap = parse_gb(info.used_swap.as_deref().unwrap_or("0"));
-    let total_swap = parse_gb(info.total_swap.as_deref().unwrap_or("0"));
-    let swap_percent = if total_swap > 0.0 {
-        (used_swap / total_swap * 100.0).round()
-    } else {
-        0.0
-    };
-    let swap_val = format!(
-        "{} / {} ({:.0}%)",
-        info.used_swap.as_deref().unwrap_or("N/A"),
-        info.total_swap.as_deref().unwrap_or("N/A"),
-        swap_percent
-    );
-
-    // ok so like, only add stuff if the config says so lol
+    let bytes = ByteFormat::from_config(config);
+    let mem_val = bytes.usage(info.used_memory_bytes, info.total_memory_bytes);
+    let swap_val = bytes.usage(info.used_swap_bytes, info.total_swap_bytes);
+
+    // one row per module, in whatever order the config lists them
     let mut info_pairs = Vec::new();
-    if config.show_distro.unwrap_or(true) {
-        info_pairs.push(("Distro".to_string(), info.distro.clone()));
-    }
-    if config.show_distro_id.unwrap_or(true) {
-        info_pairs.push(("Distro ID".to_string(), info.distro_id.clone()));
-    }
-    if config.show_kernel.unwrap_or(true) {
-        info_pairs.push(("Kernel".to_string(), info.kernel.clone()));
-    }
-    if config.show_cpu.unwrap_or(true) {
-        info_pairs.push((
-            "CPU".to_string(),
-            info.cpu.as_deref().unwrap_or("N/A").to_string(),
-        ));
-    }
-    if config.show_gpu.unwrap_or(true) {
-        info_pairs.push((
-            "GPU".to_string(),
-            info.gpu.as_deref().unwrap_or("N/A").to_string(),
-        ));
-    }
-    if config.show_memory.unwrap_or(true) {
-        info_pairs.push(("Memory".to_string(), mem_val.clone()));
-    }
-    if config.show_swap.unwrap_or(true) {
-        info_pairs.push(("Swap".to_string(), swap_val.clone()));
-    }
-    if config.show_local_ip.unwrap_or(true) {
-        info_pairs.push((
-            "Local IP".to_string(),
-            info.local_ip.as_deref().unwrap_or("N/A").to_string(),
-        ));
-    }
-    if config.show_battery.unwrap_or(true) {
-        info_pairs.push((
-            "Battery".to_string(),
-            info.battery.as_deref().unwrap_or("N/A").to_string(),
-        ));
-    }
-    if config.show_uptime.unwrap_or(true) {
-        info_pairs.push((
-            "Uptime".to_string(),
-            info.uptime.as_deref().unwrap_or("N/A").to_string(),
-        ));
-    }
-
-    // FIX: store disk label/value as String to avoid reference issues
-    for disk in &info.storage {
-        let label = format!("Disk ({})", disk.name);
-        let ro = if disk.readonly { " [Read-only]" } else { "" };
-        let value = format!(
-            "{} / {} ({}%) - {}{}",
-            disk.used, disk.total, disk.percent, disk.fs_type, ro
-        );
-        info_pairs.push((label, value));
+    // the colons line up behind the longest module label. "Disk (/mnt/...)" rows are left
+    // out of that and just run over, or one deep mount would push every colon across
+    let mut label_width = 0;
+    for (kind, label) in config.modules() {
+        label_width = label_width.max(visible_width(&label));
+        match kind {
+            ModuleKind::Distro => info_pairs.push((label, info.distro.clone())),
+            ModuleKind::DistroId => info_pairs.push((label, info.distro_id.clone())),
+            ModuleKind::Kernel => info_pairs.push((label, info.kernel.clone())),
+            ModuleKind::Cpu => info_pairs.push((label, info.cpu.as_ref().map_or("N/A".to_string(), format_cpu))),
+            ModuleKind::Gpu => {
+                if info.gpus.is_empty() {
+                    info_pairs.push((label.clone(), "Unknown".to_string()));
+                }
+                // one row per adapter so hybrid laptops show both: "GPU", "GPU 2", ...
+                for (i, gpu) in info.gpus.iter().enumerate() {
+                    let label = if i == 0 { label.clone() } else { format!("{} {}", label, i + 1) };
+                    label_width = label_width.max(visible_width(&label));
+                    info_pairs.push((label, format_gpu(gpu, &bytes)));
+                }
+            }
+            ModuleKind::Memory => info_pairs.push((label, mem_val.clone())),
+            ModuleKind::Swap => info_pairs.push((label, swap_val.clone())),
+            ModuleKind::LocalIp => {
+                info_pairs.push((label, info.local_ip.as_deref().unwrap_or("N/A").to_string()))
+            }
+            ModuleKind::Battery => {
+                info_pairs.push((label, info.battery.as_ref().map_or("N/A".to_string(), format_battery)))
+            }
+            ModuleKind::Uptime => info_pairs.push((label, info.uptime.map_or("N/A".to_string(), format_uptime))),
+            // "Disk (/home)"
+            ModuleKind::Storage => {
+                for disk in &info.storage {
+                    let ro = if disk.readonly { " [Read-only]" } else { "" };
+                    let value = format!(
+                        "{} / {} ({:.0}%) - {}{}",
+                        bytes.format(disk.used_bytes),
+                        bytes.format(disk.total_bytes),
+                        disk.percent,
+                        disk.fs_type,
+                        ro
+                    );
+                    info_pairs.push((format!("{} ({})", label, disk.name), value));
+                }
+            }
+        }
     }
 
     // combine username and hostname into "User@Host"
@@ -138,90 +205,347 @@ pub fn display_output(logo: String, info: &SystemInfo, config: &Config) {
 
     // calculate the max content width needed
     let left_pad = "  ";
-    let label_width = 10;
-
-
-    let mut max_content = "System Information".len();
+    let mut max_content = visible_width(&title);
     for (label, value) in &info_pairs {
         for line in value.lines() {
-            let content = format!("{:label_width$}: {}", label, line, label_width = label_width);
-            if content.len() > max_content {
-                max_content = content.len();
-            }
+            max_content = max_content.max(visible_width(&box_content(label, line, label_width)));
         }
     }
     let box_width = max_content + left_pad.len() + 2; // +2 for borders
 
-    let mut info_lines = vec![
-        format!("┌{:─<width$}┐", "", width = box_width - 2),
-        pad_box_title(&title, box_width),
-        format!("├{:─<width$}┤", "", width = box_width - 2),
-    ];
+    let frame = Frame::from_style(config.box_style.as_ref());
+    let borderless = config.box_style.as_ref().and_then(BoxStyle::preset) == Some(BoxPreset::None);
+    let mut info_lines = Vec::new();
+    if !borderless {
+        info_lines.push(frame.rule(frame.top_left, frame.top_right, box_width));
+    }
+    info_lines.push(pad_box_title(&title, box_width, &frame));
+    if !borderless {
+        info_lines.push(frame.rule(frame.left_tee, frame.right_tee, box_width));
+    }
     for (label, value) in &info_pairs {
         for (i, line) in value.lines().enumerate() {
             let label_str = if i == 0 { label } else { "" };
-            info_lines.push(pad_box_line(label_str, line, box_width));
+            info_lines.push(pad_box_line(label_str, line, label_width, box_width, &frame));
         }
     }
-    info_lines.push(format!("└{:─<width$}┘", "", width = box_width - 2));
+    if !borderless {
+        info_lines.push(frame.rule(frame.bottom_left, frame.bottom_right, box_width));
+    }
 
-    let logo_width = logo_lines.iter().map(|l| visible_width(l)).max().unwrap_or(0);
-    let info_width = box_width;
-    let total_width = logo_width + 4 + info_width;
-    let term_width = 80;
-    let pad_left = if term_width > total_width {
-        (term_width - total_width) / 2
-    } else {
-        0
+    let info_color = config.color.as_deref().and_then(parse_hex_color).unwrap_or(Color::White);
+    let info_lines: Vec<String> = info_lines
+        .iter()
+        .map(|line| format!("{}{}{}", SetForegroundColor(info_color), line, ResetColor))
+        .collect();
+
+    let layout = config.layout.as_ref();
+    let position = layout.and_then(|l| l.logo_position).unwrap_or(LogoPosition::Left);
+    let gap = layout.and_then(|l| l.gap).unwrap_or(DEFAULT_GAP) as usize;
+    let align = layout.and_then(|l| l.align).unwrap_or(VerticalAlign::Top);
+
+    // narrow terminal (tmux panes and the like): try the small logo next to the box, then
+    // put the logo on top of the box, and only leave it out when even that won't fit
+    let term_width = terminal_width();
+    let logos = [Some((&logo.text, true)), logo.small.as_ref().map(|small| (small, false))];
+    let stacked_logo = || logos.iter().flatten().find(|(text, _)| text_width(text) <= term_width).copied();
+    let (logo_text, position) = match position {
+        LogoPosition::None => (None, LogoPosition::None),
+        LogoPosition::Top | LogoPosition::Bottom => (stacked_logo(), position),
+        LogoPosition::Left | LogoPosition::Right => {
+            match logos.iter().flatten().find(|(text, _)| text_width(text) + gap + box_width <= term_width) {
+                Some(&beside) => (Some(beside), position),
+                None => (stacked_logo(), LogoPosition::Top),
+            }
+        }
     };
-    let total_lines = logo_lines.len().max(info_lines.len());
+    let logo_lines: Vec<&str> = logo_text.map_or(Vec::new(), |(text, _)| text.lines().collect());
+    let logo_width = logo_lines.iter().map(|l| visible_width(l)).max().unwrap_or(0);
 
-    // get colors from config
-    let logo_color = config.logo_color.as_deref().and_then(parse_hex_color).unwrap_or(Color::White);
-    let info_color = config.color.as_deref().and_then(parse_hex_color).unwrap_or(Color::White);
+    let mut rows = Vec::new();
+    // where the first logo line ended up, for drawing an image logo over it
+    let (logo_row, logo_column);
+    match position {
+        LogoPosition::Left | LogoPosition::Right => {
+            let height = logo_lines.len().max(info_lines.len());
+            let offset = |lines: usize| match align {
+                VerticalAlign::Top => 0,
+                VerticalAlign::Center => (height - lines) / 2,
+            };
+            let (logo_offset, info_offset) = (offset(logo_lines.len()), offset(info_lines.len()));
+            let pad_left = term_width.saturating_sub(logo_width + gap + box_width) / 2;
+            logo_row = logo_offset;
+            logo_column = match position {
+                LogoPosition::Left => pad_left,
+                _ => pad_left + box_width + gap,
+            };
+            for i in 0..height {
+                // logo lines bring their own colors and reset at the end
+                let logo_part = i.checked_sub(logo_offset).and_then(|i| logo_lines.get(i)).map_or("", |v| *v);
+                let info_part = i.checked_sub(info_offset).and_then(|i| info_lines.get(i)).map_or("", |s| s.as_str());
+                let (left, left_width, right) = match position {
+                    LogoPosition::Left => (logo_part, logo_width, info_part),
+                    _ => (info_part, box_width, logo_part),
+                };
+                let row = format!("{}{}{}{}", " ".repeat(pad_left), pad_to(left, left_width), " ".repeat(gap), right);
+                rows.push(row.trim_end().to_string());
+            }
+        }
+        _ => {
+            let centered = |width: usize| " ".repeat(term_width.saturating_sub(width) / 2);
+            logo_column = term_width.saturating_sub(logo_width) / 2;
+            let logo_rows = logo_lines.iter().map(|line| format!("{}{}", centered(logo_width), line));
+            let info_rows = info_lines.iter().map(|line| format!("{}{}", centered(box_width), line));
+            if position == LogoPosition::Bottom {
+                logo_row = info_lines.len();
+                rows.extend(info_rows.chain(logo_rows));
+            } else {
+                logo_row = 0;
+                rows.extend(logo_rows.chain(info_rows));
+            }
+        }
+    }
+    for row in &rows {
+        println!("{}", row);
+    }
+
+    // an image logo goes over the blank space that was printed for it: save the cursor,
+    // jump back up to its first line, draw, and come back
+    if let (Some(image), Some((_, true))) = (&logo.image, logo_text) {
+        print!("\x1b7\x1b[{}A\r", rows.len() - logo_row);
+        if logo_column > 0 {
+            print!("\x1b[{}C", logo_column);
+        }
+        print!("{}\x1b8", image.escape);
+    }
+
+    io::stdout().flush().unwrap();
+}
+
+fn text_width(text: &str) -> usize {
+    text.lines().map(visible_width).max().unwrap_or(0)
+}
+
+// the real width when there's a terminal, $COLUMNS when there isn't one (piped, some CI),
+// and the good old 80 when neither says anything
+fn terminal_width() -> usize {
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+        .filter(|&columns| columns > 0)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(80)
+}
+
+// units + precision from the config, shared by memory, swap and every disk row
+struct ByteFormat {
+    base: f64,
+    suffixes: [&'static str; 6],
+    // iec/si stick to GiB/GB like the old output did, auto picks the unit per number
+    scale: bool,
+    precision: usize,
+}
+
+// index of GiB/GB in the suffixes
+const GIGA: usize = 3;
+
+impl ByteFormat {
+    fn from_config(config: &Config) -> Self {
+        let units = config.units.unwrap_or(ByteUnits::Auto);
+        let (base, suffixes) = match units {
+            ByteUnits::Si => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB"]),
+            _ => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB"]),
+        };
+        Self {
+            base,
+            suffixes,
+            scale: units == ByteUnits::Auto,
+            precision: config.precision.unwrap_or(2).min(MAX_PRECISION) as usize,
+        }
+    }
 
-    for i in 0..total_lines {
-    let logo_part = logo_lines.get(i).map_or("", |v| *v);
-    let info_part = info_lines.get(i).map_or("", |s| s.as_str());
+    // auto scales to the biggest unit that keeps the number >= 1, so 512 MiB isn't "0.50 GiB"
+    fn format(&self, bytes: u64) -> String {
+        let unit = if self.scale {
+            let mut unit = 0;
+            let mut value = bytes as f64;
+            while value >= self.base && unit < self.suffixes.len() - 1 {
+                value /= self.base;
+                unit += 1;
+            }
+            unit
+        } else {
+            GIGA
+        };
+        if unit == 0 {
+            format!("{} {}", bytes, self.suffixes[0])
+        } else {
+            let value = bytes as f64 / self.base.powi(unit as i32);
+            format!("{:.prec$} {}", value, self.suffixes[unit], prec = self.precision)
+        }
+    }
 
-    // Print left padding
-    print!("{space:>pad$}", space = "", pad = pad_left);
+    // "0.73 GiB / 5.87 GiB (12%)"
+    fn usage(&self, used: Option<u64>, total: Option<u64>) -> String {
+        format!(
+            "{} / {} ({:.0}%)",
+            used.map_or("N/A".to_string(), |b| self.format(b)),
+            total.map_or("N/A".to_string(), |b| self.format(b)),
+            percent_of(used.unwrap_or(0), total.unwrap_or(0))
+        )
+    }
+}
 
-    print!("{}", SetForegroundColor(logo_color));
-    let logo_visible = visible_width(logo_part);
-    let pad_amount = logo_width.saturating_sub(logo_visible);
-    print!("{}{}", logo_part, " ".repeat(pad_amount));
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let days = secs / 86400;
+    let hours = (secs % 86400) / 3600;
+    let minutes = (secs % 3600) / 60;
+    format!("{}d {}h {}m", days, hours, minutes)
+}
 
-    let info_string = if !info_part.is_empty() {
-        format!("    {}", info_part)
+fn format_cpu(cpu: &CpuInfo) -> String {
+    format!(
+        "{} ({} cores) ({:.2} GHz)",
+        cpu.brand,
+        cpu.cores,
+        cpu.frequency_mhz as f64 / 1000.0
+    )
+}
+
+// "AMD Radeon RX 6800 - Discrete, 16.00 GiB VRAM, amdgpu"
+fn format_gpu(gpu: &GpuInfo, bytes: &ByteFormat) -> String {
+    let mut details = Vec::new();
+    match gpu.kind {
+        GpuKind::Integrated => details.push("Integrated".to_string()),
+        GpuKind::Discrete => details.push("Discrete".to_string()),
+        GpuKind::Virtual => details.push("Virtual".to_string()),
+        GpuKind::Unknown => {}
+    }
+    if let Some(vram) = gpu.vram_bytes {
+        details.push(format!("{} VRAM", bytes.format(vram)));
+    }
+    if let Some(driver) = &gpu.driver {
+        details.push(driver.clone());
+    }
+    let mut value = if details.is_empty() {
+        gpu.name.clone()
     } else {
-        format!("{:info_pad$}", "", info_pad = info_width + 4)
+        format!("{} - {}", gpu.name, details.join(", "))
     };
-    print!("{}{}", SetForegroundColor(info_color), info_string);
+    // live stats go on a second line right under the card they belong to
+    if let Some(stats) = &gpu.stats {
+        value.push('\n');
+        value.push_str(&format_gpu_stats(stats));
+    }
+    value
+}
 
-    println!("{}", ResetColor);
+// "54°C, 12% busy, 1850 MHz"
+fn format_gpu_stats(stats: &GpuStats) -> String {
+    let mut parts = Vec::new();
+    if let Some(temp) = stats.temperature_celsius {
+        parts.push(format!("{:.0}°C", temp));
+    }
+    if let Some(busy) = stats.busy_percent {
+        parts.push(format!("{}% busy", busy));
+    }
+    if let Some(clock) = stats.clock_mhz {
+        parts.push(format!("{} MHz", clock));
+    }
+    parts.join(", ")
 }
 
-    io::stdout().flush().unwrap();
+fn format_battery(battery: &BatteryInfo) -> String {
+    let plugged = if matches!(battery.state, BatteryState::Charging | BatteryState::Full) {
+        "[AC Connected]"
+    } else {
+        "[Discharging]"
+    };
+    format!("{}% {}", battery.percent.round(), plugged)
 }
 
-// function to parse "12.34 GB" -> 12.34
-fn parse_gb(s: &str) -> f64 {
-    s.split_whitespace()
-        .next()
-        .and_then(|num| num.parse::<f64>().ok())
-        .unwrap_or(0.0)
+// "#FF0000, #00FF00 #0000FF" -> ["#FF0000", "#00FF00", "#0000FF"]
+pub(crate) fn split_colors(list: &str) -> Vec<&str> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .collect()
 }
 
 pub(crate) fn parse_hex_color(hex: &str) -> Option<Color> {
     let hex = hex.trim_start_matches('#');
-    if hex.len() == 6 {
-        if let Ok(rgb) = u32::from_str_radix(hex, 16) {
-            let r = ((rgb >> 16) & 0xFF) as u8;
-            let g = ((rgb >> 8) & 0xFF) as u8;
-            let b = (rgb & 0xFF) as u8;
-            return Some(Color::Rgb { r, g, b });
-        }
+    if hex.len() == 6
+        && let Ok(rgb) = u32::from_str_radix(hex, 16)
+    {
+        let r = ((rgb >> 16) & 0xFF) as u8;
+        let g = ((rgb >> 8) & 0xFF) as u8;
+        let b = (rgb & 0xFF) as u8;
+        return Some(Color::Rgb { r, g, b });
     }
     None
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_and_colors() {
+        assert_eq!(visible_width("Kernel"), 6);
+        assert_eq!(visible_width("\x1b[38;2;23;147;209m/\\\x1b[0m"), 2);
+        assert_eq!(visible_width("Intel® Core™ i7"), 15);
+    }
+
+    #[test]
+    fn cjk_is_double_width() {
+        assert_eq!(visible_width("東京"), 4);
+        assert_eq!(visible_width("user@ホスト"), 11);
+    }
+
+    #[test]
+    fn emoji_and_graphemes() {
+        assert_eq!(visible_width("🦀"), 2);
+        // family emoji: three people glued together with zero width joiners
+        assert_eq!(visible_width("👨\u{200d}👩\u{200d}👧"), 2);
+        // e + combining acute
+        assert_eq!(visible_width("e\u{301}"), 1);
+    }
+
+    fn bytes(units: ByteUnits) -> ByteFormat {
+        ByteFormat::from_config(&Config { units: Some(units), ..Config::default() })
+    }
+
+    #[test]
+    fn byte_units() {
+        let auto = bytes(ByteUnits::Auto);
+        assert_eq!(auto.format(512 << 20), "512.00 MiB");
+        assert_eq!(auto.format(3 << 40), "3.00 TiB");
+        assert_eq!(auto.format(900), "900 B");
+        assert_eq!(bytes(ByteUnits::Iec).format(512 << 20), "0.50 GiB");
+        assert_eq!(bytes(ByteUnits::Iec).format(3 << 40), "3072.00 GiB");
+        assert_eq!(bytes(ByteUnits::Si).format(1_500_000_000), "1.50 GB");
+        assert_eq!(bytes(ByteUnits::Si).format(512_000_000), "0.51 GB");
+    }
+
+    #[test]
+    fn wide_border_chars_fall_back() {
+        let style = BoxStyle::Custom(crate::config::BoxChars {
+            top_left: Some("＊".to_string()),
+            vertical: Some("!".to_string()),
+            ..Default::default()
+        });
+        let frame = Frame::from_style(Some(&style));
+        assert_eq!(frame.top_left, SINGLE.top_left);
+        assert_eq!(frame.vertical, '!');
+        assert_eq!(visible_width(&frame.rule(frame.top_left, frame.top_right, 20)), 20);
+    }
+
+    #[test]
+    fn box_lines_line_up() {
+        let width = 40;
+        for value in ["Intel(R) Xeon(R)", "東京のサーバー", "🦀 crab box", "Ryzen™ 7 5800X®"] {
+            assert_eq!(visible_width(&pad_box_line("Host", value, 10, width, &SINGLE)), width, "{}", value);
+        }
+        assert_eq!(visible_width(&pad_box_title("ユーザー@ホスト | System Information", 50, &SINGLE)), 50);
+    }
+}